@@ -1,85 +1,272 @@
-use adventofcode_2021::{
-    day06, day11, day12, day15, day17, day18, day19, day20, day21, day22, day23, parse_file,
-    parse_file_lines,
+//! Benchmarks parsing and both parts of every registered day on its puzzle input.
+//!
+//! Each day is a criterion group `day NN` with the benchmarks `parse`, `part 1` and `part 2`, so
+//! `cargo bench -- "day 16/"` runs a single day. To compare against an earlier state of the code:
+//!
+//! ```text
+//! cargo bench -- --save-baseline before
+//! # ...make changes...
+//! cargo bench -- --baseline before
+//! ```
+//!
+//! Changes beyond the noise threshold are reported as "Performance has regressed" or "improved".
+//!
+//! Alternative implementations are compared in their own groups, like `day 18 snailfish`.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use adventofcode_2021::day16::{Bits, Kind, Packet};
+use adventofcode_2021::day18::{FlatSnailfish, Snailfish};
+use adventofcode_2021::day22::{reboot, Cuboid, Octree, Reactor, ReactorCore};
+use adventofcode_2021::day24::{Bytecode, Instruction, Interpreter, Variable};
+use adventofcode_2021::{days, try_parse_str_lines, Part, Puzzle};
+use criterion::measurement::WallTime;
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkGroup, Criterion, Throughput,
 };
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-fn day_6(c: &mut Criterion) {
-    let input: day06::School = parse_file("src/day06/input.txt");
-    c.bench_function("day 6", |b| {
-        b.iter(|| day06::part_2(black_box(input.clone())));
-    });
-}
+/// Parts slower than this get the minimum sample count so a full run stays reasonable
+const SLOW: Duration = Duration::from_millis(100);
 
-fn day_11(c: &mut Criterion) {
-    let input: day11::Cavern = parse_file("src/day11/input.txt");
-    c.bench_function("day 11", |b| {
-        b.iter(|| day11::part_2(black_box(input.clone())));
-    });
+/// Picks the sample count from a single untimed run of `f`
+fn adjust_sample_size(group: &mut BenchmarkGroup<'_, WallTime>, f: impl FnOnce()) {
+    let start = Instant::now();
+    f();
+    group.sample_size(if start.elapsed() > SLOW { 10 } else { 100 });
 }
 
-fn day_12(c: &mut Criterion) {
-    let input: day12::CaveSystem = parse_file("src/day12/input.txt");
-    c.bench_function("day 12", |b| {
-        b.iter(|| day12::part_2(black_box(&input)));
-    });
+fn read_input(day: u8) -> (String, String) {
+    let path = format!("{}/src/day{:02}/input.txt", env!("CARGO_MANIFEST_DIR"), day);
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    (path, input)
 }
 
-fn day_15(c: &mut Criterion) {
-    let input: day15::CavernInput = parse_file("src/day15/input.txt");
-    c.bench_function("day 15", |b| {
-        b.iter(|| day15::part_2(black_box(input.clone())));
-    });
-}
+fn bench_day(c: &mut Criterion, puzzle: &dyn Puzzle) {
+    let day = puzzle.day();
+    let (path, input) = read_input(day);
+    let parsed = puzzle
+        .parse(&input)
+        .unwrap_or_else(|err| panic!("{} ({})", err, path));
 
-fn day_17(c: &mut Criterion) {
-    let input: day17::TargetArea = parse_file("src/day17/input.txt");
-    c.bench_function("day 17", |b| {
-        b.iter(|| day17::part_2(black_box(input)));
-    });
+    let mut group = c.benchmark_group(format!("day {:02}", day));
+    group.bench_function("parse", |b| b.iter(|| puzzle.parse(black_box(&input))));
+    for part in [Part::One, Part::Two] {
+        adjust_sample_size(&mut group, || {
+            puzzle.solve(parsed.as_ref(), part);
+        });
+        group.bench_function(format!("part {}", part), |b| {
+            b.iter(|| puzzle.solve(black_box(parsed.as_ref()), part));
+        });
+    }
+    group.finish();
 }
 
-fn day_18(c: &mut Criterion) {
-    c.bench_function("day 18", |b| {
-        b.iter(|| day18::part_2(black_box(parse_file_lines("src/day18/input.txt"))));
-    });
+fn all_days(c: &mut Criterion) {
+    for puzzle in days() {
+        bench_day(c, *puzzle);
+    }
 }
 
-fn day_19(c: &mut Criterion) {
-    let input: day19::BeaconMap = parse_file("src/day19/input.txt");
-    c.bench_function("day 19", |b| {
-        b.iter(|| day19::part_2(black_box(input.clone())));
+/// Reading a transmission of several kilobytes, much larger than the puzzle input
+fn day16_bits(c: &mut Criterion) {
+    let sums = (0..40)
+        .map(|sum| {
+            let literals = (0..100)
+                .map(|n: u64| Packet::literal((n % 8) as u8, (sum * 100 + n) * 2_654_435_761))
+                .collect();
+            Packet::operator(1, Kind::Sum, literals)
+        })
+        .collect();
+    let hex = Packet::operator(0, Kind::Sum, sums).to_hex().unwrap();
+    let bits: Bits = hex.parse().unwrap();
+
+    let bools = bools::parse_hex(&hex).unwrap();
+    assert_eq!(Packet::decode(&bits).ok(), bools::decode(&bools));
+
+    let mut group = c.benchmark_group("day 16 bits");
+    group.throughput(Throughput::Bytes(hex.len() as u64 / 2));
+    group.bench_function("parse hex", |b| {
+        b.iter(|| black_box(&hex).parse::<Bits>().unwrap());
+    });
+    group.bench_function("decode", |b| {
+        b.iter(|| Packet::decode(black_box(&bits)).unwrap());
+    });
+    group.bench_function("parse hex as bools", |b| {
+        b.iter(|| bools::parse_hex(black_box(&hex)).unwrap());
+    });
+    group.bench_function("decode bools", |b| {
+        b.iter(|| bools::decode(black_box(&bools)).unwrap());
     });
+    group.finish();
 }
 
-fn day_20(c: &mut Criterion) {
-    let input: day20::Input = parse_file("src/day20/input.txt");
-    c.bench_function("day 20", |b| {
-        b.iter(|| day20::part_2(black_box(input.clone())));
-    });
+/// The `Vec<bool>` transmission and reader day 16 used before packing bits into bytes, only kept
+/// to compare against. Errors are `None`.
+mod bools {
+    use adventofcode_2021::day16::{Kind, LengthType, Packet};
+
+    pub fn parse_hex(hex: &str) -> Option<Vec<bool>> {
+        let mut bits = Vec::with_capacity(4 * hex.len());
+        for c in hex.chars() {
+            let digit = c.to_digit(16)?;
+            bits.extend((0..4).rev().map(|bit| digit >> bit & 1 == 1));
+        }
+        Some(bits)
+    }
+
+    struct Reader<'a> {
+        buf: &'a [bool],
+    }
+
+    impl<'a> Reader<'a> {
+        fn advance(&mut self, n: usize) -> Option<&'a [bool]> {
+            if n > self.buf.len() {
+                return None;
+            }
+            let (bits, rest) = self.buf.split_at(n);
+            self.buf = rest;
+            Some(bits)
+        }
+
+        fn read(&mut self, n: usize) -> Option<u64> {
+            let bits = self.advance(n)?;
+            Some(
+                bits.iter()
+                    .fold(0, |value, &bit| value << 1 | u64::from(bit)),
+            )
+        }
+    }
+
+    pub fn decode(bits: &[bool]) -> Option<Packet> {
+        let mut reader = Reader { buf: bits };
+        let packet = parse(&mut reader)?;
+        (!reader.buf.contains(&true)).then_some(packet)
+    }
+
+    fn parse(reader: &mut Reader) -> Option<Packet> {
+        let version = reader.read(3)? as u8;
+        let kind = match Kind::from_type_id(reader.read(3)? as u8) {
+            Some(kind) => kind,
+            None => return literal(version, reader),
+        };
+        let mut packets = Vec::new();
+        let length_type = if reader.read(1)? == 1 {
+            for _ in 0..reader.read(11)? {
+                packets.push(parse(reader)?);
+            }
+            LengthType::Count
+        } else {
+            let length = reader.read(15)? as usize;
+            let mut packet_reader = Reader {
+                buf: reader.advance(length)?,
+            };
+            while !packet_reader.buf.is_empty() {
+                packets.push(parse(&mut packet_reader)?);
+            }
+            LengthType::Bits
+        };
+        kind.accepts(packets.len())
+            .then(|| Packet::operator(version, kind, packets).with_length_type(length_type))
+    }
+
+    fn literal(version: u8, reader: &mut Reader) -> Option<Packet> {
+        let mut value: u64 = 0;
+        let mut groups = 0;
+        loop {
+            let more = reader.read(1)? == 1;
+            if value.leading_zeros() < 4 {
+                return None;
+            }
+            value = value << 4 | reader.read(4)?;
+            groups += 1;
+            if !more {
+                return Some(Packet::literal(version, value).with_groups(groups));
+            }
+        }
+    }
 }
 
-fn day_21(c: &mut Criterion) {
-    let input: day21::Game = parse_file("src/day21/input.txt");
-    c.bench_function("day 21", |b| {
-        b.iter(|| day21::part_2(black_box(input)));
+/// Adding up the puzzle input as boxed trees and as flat arrays
+fn day18_snailfish(c: &mut Criterion) {
+    let (_, input) = read_input(18);
+    let trees: Vec<Snailfish> = try_parse_str_lines(&input).unwrap();
+    let flats: Vec<FlatSnailfish> = trees
+        .iter()
+        .map(|tree| FlatSnailfish::try_from(tree).unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("day 18 snailfish");
+    group.throughput(Throughput::Elements(trees.len() as u64));
+    group.bench_function("boxed", |b| {
+        b.iter(|| {
+            let sum = black_box(&trees).iter().cloned().reduce(|a, b| a + b);
+            sum.unwrap().magnitude()
+        });
+    });
+    group.bench_function("flat", |b| {
+        b.iter(|| {
+            let sum = black_box(&flats).iter().copied().reduce(|a, b| a + b);
+            sum.unwrap().magnitude()
+        });
     });
+    group.finish();
 }
 
-fn day_22(c: &mut Criterion) {
-    c.bench_function("day 22", |b| {
-        b.iter(|| day22::part_2(black_box(parse_file_lines("src/day22/input.txt"))));
+/// Running every reboot step with inclusion-exclusion and with an octree
+fn day22_reactor(c: &mut Criterion) {
+    let (_, input) = read_input(22);
+    let steps: Vec<Cuboid> = try_parse_str_lines(&input).unwrap();
+
+    let mut group = c.benchmark_group("day 22 reactor");
+    group.throughput(Throughput::Elements(steps.len() as u64));
+    group.bench_function("core", |b| {
+        b.iter(|| reboot::<ReactorCore>(black_box(&steps).iter().copied()).count());
+    });
+    group.bench_function("octree", |b| {
+        b.iter(|| reboot::<Octree>(black_box(&steps).iter().copied()).count());
     });
+    group.finish();
 }
 
-fn day_23(c: &mut Criterion) {
-    let burrow: day23::Burrow<2> = parse_file("src/day23/input.txt");
-    c.bench_function("day 21", |b| {
-        b.iter(|| day23::part_2(black_box(burrow)));
+/// Checking model numbers with the interpreter against the compiled program
+fn day24_alu(c: &mut Criterion) {
+    let (_, input) = read_input(24);
+    let program: Vec<Instruction> = try_parse_str_lines(&input).unwrap();
+    let bytecode = Bytecode::compile(&program, Variable::Z);
+    let model_numbers: Vec<u64> = (0..1000)
+        .map(|n| {
+            // Base 9 digits of a spread out `n`, shifted to the valid model number digits 1-9
+            let (model_number, _) = (0..14).fold((0, n * 1_111_111), |(number, rest), _| {
+                (number * 10 + 9 - rest % 9, rest / 9)
+            });
+            model_number
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("day 24 alu");
+    group.throughput(Throughput::Elements(model_numbers.len() as u64));
+    group.bench_function("interpreter", |b| {
+        b.iter(|| {
+            for &model_number in &model_numbers {
+                let mut interpreter = Interpreter::for_model_number(model_number);
+                black_box(interpreter.run(&program).is_ok() && interpreter.get(Variable::Z) == 0);
+            }
+        });
+    });
+    group.bench_function("bytecode", |b| {
+        b.iter(|| {
+            for &model_number in &model_numbers {
+                black_box(bytecode.is_valid(model_number));
+            }
+        });
     });
+    group.finish();
 }
 
-criterion_group!(
-    benches, day_6, day_11, day_12, day_15, day_17, day_18, day_19, day_20, day_21, day_22, day_23
-);
+criterion_group! {
+    name = benches;
+    config = Criterion::default().noise_threshold(0.05);
+    targets = all_days, day16_bits, day18_snailfish, day22_reactor, day24_alu
+}
 criterion_main!(benches);
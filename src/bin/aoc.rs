@@ -0,0 +1,190 @@
+use std::io::{self, Read};
+use std::process;
+use std::time::{Duration, Instant};
+use std::{env, fs};
+
+use adventofcode_2021::{day, days, Error, Manifest, Part, Puzzle};
+
+const USAGE: &str = "\
+usage: aoc run <day|all> [--part <1|2>] [--input <path>]
+       aoc verify [<manifest>]
+
+  <day>           day number, 1 to 24
+  all             run every registered day on its default input
+  --part, -p      only run the given part (default: both)
+  --input, -i     read the puzzle input from <path>, or stdin when <path> is '-'
+                  (default: src/dayNN/input.txt)
+  <manifest>      expected answers to check, one '<day> <part> <input> <answer>' per line
+                  (default: answers.txt)";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Selection {
+    All,
+    Day(u8),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Run(Args),
+    Verify(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Args {
+    selection: Selection,
+    part: Option<Part>,
+    input: Option<String>,
+}
+
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        match args.next().as_deref() {
+            Some("run") => Args::parse(args).map(Self::Run),
+            Some("verify") => {
+                let manifest = args.next().unwrap_or_else(default_manifest);
+                match args.next() {
+                    Some(arg) => Err(format!("unexpected argument '{}'", arg)),
+                    None => Ok(Self::Verify(manifest)),
+                }
+            }
+            Some(command) => Err(format!("unknown command '{}'", command)),
+            None => Err("missing command".to_owned()),
+        }
+    }
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let selection = match args.next().as_deref() {
+            Some("all") => Selection::All,
+            Some(number) => Selection::Day(
+                number
+                    .parse()
+                    .map_err(|_| format!("invalid day '{}'", number))?,
+            ),
+            None => return Err("missing day".to_owned()),
+        };
+        let mut part = None;
+        let mut input = None;
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for '{}'", flag))?;
+            match flag.as_str() {
+                "--part" | "-p" => part = Some(value.parse().map_err(|err: Error| err.message)?),
+                "--input" | "-i" => input = Some(value),
+                _ => return Err(format!("unknown option '{}'", flag)),
+            }
+        }
+        if selection == Selection::All && input.is_some() {
+            return Err("--input can only be used with a single day".to_owned());
+        }
+        Ok(Self {
+            selection,
+            part,
+            input,
+        })
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        self.part
+            .map_or_else(|| vec![Part::One, Part::Two], |part| vec![part])
+    }
+}
+
+fn default_input(day: u8) -> String {
+    format!("{}/src/day{:02}/input.txt", env!("CARGO_MANIFEST_DIR"), day)
+}
+
+fn default_manifest() -> String {
+    format!("{}/answers.txt", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn run(puzzle: &dyn Puzzle, path: &str, parts: &[Part]) -> Result<(), String> {
+    let day = puzzle.day();
+    let input =
+        read_input(path).map_err(|err| format!("day {}: cannot read '{}': {}", day, path, err))?;
+    let (parsed, duration) = timed(|| puzzle.parse(&input));
+    let parsed = parsed.map_err(|err| format!("{} ({})", err, path))?;
+    println!("day {:02} parse  ({:?})", day, duration);
+    for &part in parts {
+        let (answer, duration) = timed(|| puzzle.solve(parsed.as_ref(), part));
+        if answer.contains('\n') {
+            println!(
+                "day {:02} part {} ({:?}):\n{}",
+                day,
+                part,
+                duration,
+                answer.trim_end()
+            );
+        } else {
+            println!("day {:02} part {}: {} ({:?})", day, part, answer, duration);
+        }
+    }
+    Ok(())
+}
+
+fn verify(path: &str) -> Result<(), String> {
+    let manifest = Manifest::load(path).map_err(|err| err.to_string())?;
+    let mut failed = 0;
+    for report in manifest.verify() {
+        println!("{}", report);
+        if !report.passed() {
+            failed += 1;
+        }
+    }
+    let total = manifest.expectations.len();
+    println!("{} passed, {} failed", total - failed, failed);
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} of {} answers did not match", failed, total))
+    }
+}
+
+fn run_all(args: Args) -> Result<(), String> {
+    let parts = args.parts();
+    match args.selection {
+        Selection::All => days()
+            .iter()
+            .try_for_each(|puzzle| run(*puzzle, &default_input(puzzle.day()), &parts)),
+        Selection::Day(number) => match day(number) {
+            Some(puzzle) => {
+                let path = args.input.unwrap_or_else(|| default_input(number));
+                run(puzzle, &path, &parts)
+            }
+            None => Err(format!("day {} is not solved", number)),
+        },
+    }
+}
+
+fn main() {
+    let command = Command::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+    let result = match command {
+        Command::Run(args) => run_all(args),
+        Command::Verify(manifest) => verify(&manifest),
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
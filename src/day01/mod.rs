@@ -1,3 +1,4 @@
+use crate::{try_parse_str_lines, Error, Solution};
 use std::collections::VecDeque;
 
 pub fn part_1<I: IntoIterator<Item = u16>>(input: I) -> u16 {
@@ -23,6 +24,28 @@ fn measure_window<I: IntoIterator<Item = u16>>(input: I, window_size: usize) ->
     count
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u16>;
+    type Part1 = u16;
+    type Part2 = u16;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file_lines;
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::{parse_part, split_once, Error};
+
 #[derive(Copy, Clone, Debug)]
 pub enum Command {
     Forward(u32),
@@ -8,15 +10,16 @@ pub enum Command {
 }
 
 impl FromStr for Command {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (cmd, arg) = input.split_once(' ').ok_or(())?;
+        let (cmd, arg) = split_once(input, " ")?;
+        let arg = parse_part(input, arg)?;
         match cmd {
-            "forward" => Ok(Self::Forward(arg.parse().unwrap())),
-            "down" => Ok(Self::Down(arg.parse().unwrap())),
-            "up" => Ok(Self::Up(arg.parse().unwrap())),
-            _ => Err(()),
+            "forward" => Ok(Self::Forward(arg)),
+            "down" => Ok(Self::Down(arg)),
+            "up" => Ok(Self::Up(arg)),
+            _ => Err(Error::at(input, cmd, format!("unknown command '{}'", cmd))),
         }
     }
 }
@@ -2,6 +2,7 @@ pub use command::Command;
 use position::NaivePosition;
 
 use crate::day02::position::FullPosition;
+use crate::{try_parse_str_lines, Error, Solution};
 
 mod command;
 mod position;
@@ -19,6 +20,28 @@ pub fn part_2<I: Iterator<Item = Command>>(instructions: I) -> u32 {
     horizontal * depth
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Command>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file_lines;
@@ -62,4 +85,13 @@ mod tests {
         let result = part_2(input);
         assert_eq!(result, 1_880_593_125);
     }
+
+    #[test]
+    fn unknown_command_is_an_error() {
+        let error = try_parse_str_lines::<Command>("forward 5\nback 3").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: unknown command 'back'",
+            error.to_string()
+        );
+    }
 }
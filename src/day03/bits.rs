@@ -2,6 +2,8 @@ use std::ops::Index;
 use std::str::FromStr;
 use std::vec;
 
+use crate::Error;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Bits(Vec<bool>);
 
@@ -33,9 +35,20 @@ impl IntoIterator for Bits {
 }
 
 impl FromStr for Bits {
-    type Err = ();
+    type Err = Error;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let bits = input.chars().map(|c| c == '1').collect();
+        let bits = input
+            .char_indices()
+            .map(|(index, c)| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(Error::at(
+                    input,
+                    &input[index..],
+                    format!("invalid bit '{}'", c),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
         Ok(Self(bits))
     }
 }
@@ -1,5 +1,6 @@
 use std::ops::Add;
 
+use crate::{try_parse_str_lines, Error, Solution};
 use bits::Bits;
 use count::BitCounts;
 
@@ -49,6 +50,28 @@ const fn xnor(a: bool, b: bool) -> bool {
     !(a ^ b)
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Bits>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        power_consumption(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        life_support_rating(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file_lines;
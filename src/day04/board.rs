@@ -1,12 +1,14 @@
 use std::str::FromStr;
 
-#[derive(Debug)]
+use crate::{parse_part, Error};
+
+#[derive(Clone, Debug)]
 pub struct Board {
     grid: [[Cell; 5]; 5],
     victory: bool,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Cell {
     number: u8,
     marked: bool,
@@ -19,11 +21,11 @@ impl Cell {
 }
 
 impl FromStr for Cell {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         Ok(Self {
-            number: input.parse().unwrap(),
+            number: input.parse()?,
             marked: false,
         })
     }
@@ -74,16 +76,28 @@ impl Board {
 }
 
 impl FromStr for Board {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let row = |input: &str| -> [Cell; 5] {
-            let mut numbers = input.split_whitespace();
-            arr_5!(numbers.next().unwrap().parse().unwrap())
+        let row = |line: &str| -> Result<[Cell; 5], Error> {
+            let mut numbers = line.split_whitespace();
+            let mut cell = || match numbers.next() {
+                Some(number) => parse_part(input, number),
+                None => Err(Error::at_end(line, "expected 5 numbers").within(input, line)),
+            };
+            let row = arr_5!(cell()?);
+            match numbers.next() {
+                Some(number) => Err(Error::at(input, number, "expected 5 numbers")),
+                None => Ok(row),
+            }
         };
         let mut lines = input.lines();
+        let mut next_row = || match lines.next() {
+            Some(line) => row(line),
+            None => Err(Error::at_end(input, "expected 5 rows")),
+        };
         Ok(Self {
-            grid: arr_5!(row(lines.next().unwrap())),
+            grid: arr_5!(next_row()?),
             victory: false,
         })
     }
@@ -1,9 +1,10 @@
+use crate::{parse_part, try_parse_str, Error, Solution};
 use board::Board;
 use std::str::FromStr;
 
 mod board;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Game {
     numbers: Vec<u8>,
     boards: Vec<Board>,
@@ -40,19 +41,42 @@ impl Game {
 }
 
 impl FromStr for Game {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut split = input.split("\n\n");
-        Ok(Self {
-            numbers: split
-                .next()
-                .unwrap()
-                .split(',')
-                .map(|s| s.parse().unwrap())
-                .collect(),
-            boards: split.map(|s| s.parse().unwrap()).collect(),
-        })
+        let numbers = split
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| parse_part(input, s))
+            .collect::<Result<_, _>>()?;
+        let boards = split
+            .map(|s| parse_part(input, s))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { numbers, boards })
+    }
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Game;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(mut input: Self::Input) -> Self::Part1 {
+        input.play_to_win()
+    }
+
+    fn part_2(mut input: Self::Input) -> Self::Part2 {
+        input.play_to_lose()
     }
 }
 
@@ -106,4 +130,11 @@ mod tests {
         let mut game: Game = parse_file("src/day04/input.txt");
         assert_eq!(4590, game.play_to_lose());
     }
+
+    #[test]
+    fn short_board_row_is_an_error() {
+        let input = EXAMPLE.replacen("\n8  2 23  4 24", "\n8  2 23  4", 1);
+        let error = input.parse::<Game>().unwrap_err();
+        assert_eq!((Some(4), Some(11)), (error.line, error.column));
+    }
 }
@@ -2,6 +2,7 @@ use std::cmp::max;
 use std::str::FromStr;
 
 use super::Point;
+use crate::{parse_part, split_once, Error};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Line {
@@ -13,11 +14,14 @@ pub struct Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (start, end) = input.split_once(" -> ").unwrap();
-        Ok(Self::new(start.parse().unwrap(), end.parse().unwrap()))
+        let (start, end) = split_once(input, " -> ")?;
+        Ok(Self::new(
+            parse_part(input, start)?,
+            parse_part(input, end)?,
+        ))
     }
 }
 
@@ -1,43 +1,34 @@
+use crate::{try_parse_str_lines, Error, Grid, Solution};
 pub use line::Line;
 pub use point::Point;
+use std::cmp::max;
 
 mod line;
 mod point;
 
 #[derive(Clone, Debug)]
-struct Diagram(Vec<Vec<u8>>);
+struct Diagram(Grid<u8>);
 
 impl Diagram {
-    const fn new() -> Self {
-        Self(Vec::new())
+    /// Empty diagram just large enough to hold every point of `lines`
+    fn new(lines: &[Line]) -> Self {
+        let (width, height) = lines
+            .iter()
+            .flat_map(Line::points)
+            .fold((0, 0), |(width, height), point| {
+                (max(width, point.x + 1), max(height, point.y + 1))
+            });
+        Self(Grid::new(width, height, 0))
     }
 
-    fn add_line(&mut self, line: Line) {
+    fn add_line(&mut self, line: &Line) {
         for point in line.points() {
-            self.add_point(point);
+            self.0[(point.x, point.y)] += 1;
         }
     }
 
-    fn row_mut(&mut self, row: usize) -> &mut Vec<u8> {
-        if self.0.len() <= row {
-            self.0.resize(row + 1, Vec::new());
-        }
-        &mut self.0[row]
-    }
-
-    fn add_point(&mut self, point: Point) {
-        let row = self.row_mut(point.y);
-        if row.len() <= point.x {
-            row.resize(point.x + 1, 0);
-        }
-        row[point.x] += 1;
-    }
-
     fn amount_of_danger_points(&self) -> usize {
-        self.0
-            .iter()
-            .map(|row| row.iter().filter(|&&x| x > 1).count())
-            .sum()
+        self.0.values().filter(|&&x| x > 1).count()
     }
 }
 
@@ -49,13 +40,36 @@ pub fn part_1<I: Iterator<Item = Line>>(lines: I) -> usize {
 }
 
 pub fn part_2<I: Iterator<Item = Line>>(lines: I) -> usize {
-    let mut diagram = Diagram::new();
-    for line in lines {
+    let lines: Vec<Line> = lines.collect();
+    let mut diagram = Diagram::new(&lines);
+    for line in &lines {
         diagram.add_line(line);
     }
     diagram.amount_of_danger_points()
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<Line>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{parse_file_lines, parse_str_lines};
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::{parse_part, split_once, Error};
+
 #[derive(Copy, Clone, Debug)]
 pub struct Point {
     pub x: usize,
@@ -7,13 +9,13 @@ pub struct Point {
 }
 
 impl FromStr for Point {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (x, y) = input.split_once(',').unwrap();
+        let (x, y) = split_once(input, ",")?;
         Ok(Self {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
+            x: parse_part(input, x)?,
+            y: parse_part(input, y)?,
         })
     }
 }
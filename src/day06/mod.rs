@@ -1,3 +1,4 @@
+use crate::{parse_part, try_parse_str, Error, Solution};
 use std::str::FromStr;
 
 #[derive(Clone, Debug)]
@@ -8,13 +9,15 @@ pub struct School {
 }
 
 impl FromStr for School {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut fish = [0; 9];
         for timer in input.split(',') {
-            let timer: usize = timer.parse().unwrap();
-            fish[timer] += 1;
+            let index: usize = parse_part(input, timer)?;
+            *fish
+                .get_mut(index)
+                .ok_or_else(|| Error::at(input, timer, "timer must be at most 8"))? += 1;
         }
         Ok(Self {
             fish,
@@ -49,6 +52,28 @@ pub fn part_2(mut school: School) -> u64 {
     school.size()
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Input = School;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
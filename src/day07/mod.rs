@@ -1,3 +1,4 @@
+use crate::{parse_part, try_parse_str, Error, Solution};
 use std::str::FromStr;
 
 #[derive(Clone, Debug)]
@@ -10,7 +11,7 @@ impl Crabs {
         self.positions.sort_unstable();
         let len = self.positions.len();
         // this check is not needed for this exercise but the method is called median so a median I shall provide
-        if len % 2 == 0 {
+        if len.is_multiple_of(2) {
             (self.positions[len / 2 - 1] + self.positions[len / 2]) / 2
         } else {
             self.positions[len / 2]
@@ -43,18 +44,17 @@ const fn cost(a: u32, b: u32) -> u32 {
 }
 
 const fn delta(a: u32, b: u32) -> u32 {
-    if a > b {
-        a - b
-    } else {
-        b - a
-    }
+    a.abs_diff(b)
 }
 
 impl FromStr for Crabs {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let heights = input.split(',').map(|s| s.parse().unwrap()).collect();
+        let heights = input
+            .split(',')
+            .map(|s| parse_part(input, s))
+            .collect::<Result<_, _>>()?;
         Ok(Self { positions: heights })
     }
 }
@@ -77,6 +77,28 @@ pub fn part_2(crabs: &Crabs) -> u32 {
         .unwrap()
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Crabs;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
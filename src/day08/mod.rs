@@ -1,3 +1,4 @@
+use crate::{parse_part, split_once, try_parse_str_lines, Error, Solution};
 use std::collections::HashSet;
 use std::str::FromStr;
 
@@ -25,14 +26,25 @@ impl Pattern {
 }
 
 impl FromStr for Pattern {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let segments = input.to_string().chars().collect();
+        let segments = input
+            .char_indices()
+            .map(|(index, c)| match c {
+                'a'..='g' => Ok(c),
+                _ => Err(Error::at(
+                    input,
+                    &input[index..],
+                    format!("invalid segment '{}'", c),
+                )),
+            })
+            .collect::<Result<_, _>>()?;
         Ok(Self { segments })
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct Entry {
     input: Vec<Pattern>,
     output: Vec<Pattern>,
@@ -82,23 +94,45 @@ pub fn part_2<I: Iterator<Item = Entry>>(entries: I) -> usize {
 }
 
 impl FromStr for Entry {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(string: &str) -> Result<Self, Self::Err> {
-        let (input, output) = string.split_once(" | ").unwrap();
+        let (input, output) = split_once(string, " | ")?;
         let parse = |patterns: &str| {
             patterns
                 .split_whitespace()
-                .map(|s| s.parse().unwrap())
-                .collect()
+                .map(|s| parse_part(string, s))
+                .collect::<Result<_, _>>()
         };
         Ok(Self {
-            input: parse(input),
-            output: parse(output),
+            input: parse(input)?,
+            output: parse(output)?,
         })
     }
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Entry>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{parse_file_lines, parse_str_lines};
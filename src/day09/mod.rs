@@ -1,9 +1,10 @@
+use crate::{try_parse_str, Error, Grid, Solution};
 use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Default, Clone, Debug)]
 pub struct HeightMap {
-    heights: Vec<Vec<u32>>,
+    heights: Grid<u32>,
 }
 
 #[derive(Clone)]
@@ -34,34 +35,11 @@ impl<'a> Entry<'a> {
         self.position.y
     }
 
-    pub fn up(&self) -> Option<Self> {
-        if self.y() == 0 {
-            None
-        } else {
-            self.map.entry(Position::new(self.x(), self.y() - 1))
-        }
-    }
-
-    pub fn down(&self) -> Option<Self> {
-        self.map.entry(Position::new(self.x(), self.y() + 1))
-    }
-
-    pub fn left(&self) -> Option<Self> {
-        if self.x() == 0 {
-            None
-        } else {
-            self.map.entry(Position::new(self.x() - 1, self.y()))
-        }
-    }
-
-    pub fn right(&self) -> Option<Self> {
-        self.map.entry(Position::new(self.x() + 1, self.y()))
-    }
-
-    pub fn neighbours(&self) -> impl Iterator<Item = Entry<'a>> {
-        [self.up(), self.down(), self.left(), self.right()]
-            .into_iter()
-            .flatten()
+    pub fn neighbours(&self) -> impl Iterator<Item = Entry<'a>> + '_ {
+        self.map
+            .heights
+            .neighbours((self.x(), self.y()))
+            .filter_map(|(x, y)| self.map.entry(Position::new(x, y)))
     }
 
     pub fn is_low_point(&self) -> bool {
@@ -91,24 +69,22 @@ impl<'a> Entry<'a> {
 }
 
 impl HeightMap {
-    pub fn entries(&self) -> impl Iterator<Item = Entry> + '_ {
-        self.heights.iter().enumerate().flat_map(move |(y, row)| {
-            row.iter().enumerate().map(move |(x, &height)| Entry {
-                position: Position { x, y },
-                height,
-                map: self,
-            })
+    pub fn entries(&self) -> impl Iterator<Item = Entry<'_>> + '_ {
+        self.heights.iter().map(move |((x, y), &height)| Entry {
+            position: Position { x, y },
+            height,
+            map: self,
         })
     }
 
-    pub fn entry(&self, position: Position) -> Option<Entry> {
-        self.heights.get(position.y).and_then(|row| {
-            row.get(position.x).map(|&height| Entry {
+    pub fn entry(&self, position: Position) -> Option<Entry<'_>> {
+        self.heights
+            .get((position.x, position.y))
+            .map(|&height| Entry {
                 position,
                 height,
                 map: self,
             })
-        })
     }
 
     pub fn low_points(&self) -> impl Iterator<Item = Entry<'_>> + '_ {
@@ -117,14 +93,12 @@ impl HeightMap {
 }
 
 impl FromStr for HeightMap {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let heights: Vec<Vec<u32>> = input
-            .lines()
-            .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-            .collect();
-        Ok(Self { heights })
+        Ok(Self {
+            heights: Grid::parse_digits(input, "height")?,
+        })
     }
 }
 
@@ -138,6 +112,28 @@ pub fn part_2(map: &HeightMap) -> usize {
     basin_sizes.into_iter().rev().take(3).product()
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Input = HeightMap;
+    type Part1 = u32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(&input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
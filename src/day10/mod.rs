@@ -1,3 +1,5 @@
+use crate::{Error, Solution};
+
 enum CharStatus {
     Open,
     Close,
@@ -76,6 +78,38 @@ pub fn part_2<I: Iterator<Item = String>>(lines: I) -> u64 {
     scores[scores.len() / 2]
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<String>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let invalid = input
+            .char_indices()
+            .find(|&(_, c)| !"()[]{}<>\n".contains(c) && c != '\r');
+        match invalid {
+            Some((index, c)) => Err(Error::at(
+                input,
+                &input[index..],
+                format!("expected a bracket, found '{}'", c),
+            )),
+            None => Ok(input.lines().map(str::to_owned).collect()),
+        }
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input.into_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{parse_file_lines, parse_str_lines};
@@ -118,4 +152,13 @@ mod tests {
         let lines = parse_file_lines("src/day10/input.txt");
         assert_eq!(2_858_785_164, part_2(lines));
     }
+
+    #[test]
+    fn non_bracket_is_an_error() {
+        let error = Day10::parse("[()]\n(x)").unwrap_err();
+        assert_eq!(
+            "line 2, column 2: expected a bracket, found 'x'",
+            error.to_string()
+        );
+    }
 }
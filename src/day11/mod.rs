@@ -1,47 +1,24 @@
-use std::cmp::min;
+use crate::{try_parse_str, Error, Grid, Solution};
 use std::str::FromStr;
 
 #[derive(Default, Clone, Debug)]
 pub struct Cavern {
-    octopuses: Vec<Vec<u8>>,
-    width: usize,
-    height: usize,
+    octopuses: Grid<u8>,
 }
 
 type Position = (usize, usize);
 
 impl Cavern {
-    fn new(octopuses: Vec<Vec<u8>>) -> Self {
-        let width = octopuses[0].len();
-        let height = octopuses.len();
-        Self {
-            octopuses,
-            width,
-            height,
-        }
-    }
-
-    pub fn neighbours(&self, (cx, cy): Position) -> impl Iterator<Item = Position> + '_ {
-        let x_min = cx.saturating_sub(1);
-        let x_max = min(cx + 1, self.width - 1);
-        let y_min = cy.saturating_sub(1);
-        let y_max = min(cy + 1, self.height - 1);
-        (x_min..=x_max).flat_map(move |x| {
-            (y_min..=y_max)
-                .filter(move |&y| x != cx || y != cy)
-                .map(move |y| (x, y))
-        })
-    }
-
-    fn positions(&self) -> impl Iterator<Item = Position> + '_ {
-        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> {
+        self.octopuses.surrounding(position)
     }
 
     fn tick(&mut self) -> usize {
-        let mut to_tick: Vec<Position> = self.positions().collect();
-        while let Some(position @ (x, y)) = to_tick.pop() {
-            self.octopuses[x][y] += 1;
-            if self.octopuses[x][y] == 10 {
+        let mut to_tick: Vec<Position> = self.octopuses.positions().collect();
+        while let Some(position) = to_tick.pop() {
+            let energy = &mut self.octopuses[position];
+            *energy += 1;
+            if *energy == 10 {
                 to_tick.extend(self.neighbours(position));
             }
         }
@@ -49,34 +26,24 @@ impl Cavern {
     }
 
     fn reset_flashes(&mut self) -> usize {
-        self.octopuses
-            .iter_mut()
-            .flat_map(move |row| row.iter_mut())
-            .fold(0, |count, energy| {
-                if *energy >= 10 {
-                    *energy = 0;
-                    count + 1
-                } else {
-                    count
-                }
-            })
+        self.octopuses.values_mut().fold(0, |count, energy| {
+            if *energy >= 10 {
+                *energy = 0;
+                count + 1
+            } else {
+                count
+            }
+        })
     }
 }
 
 impl FromStr for Cavern {
-    type Err = ();
+    type Err = Error;
 
-    #[allow(clippy::cast_possible_truncation)]
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let octopuses = input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as u8)
-                    .collect()
-            })
-            .collect();
-        Ok(Self::new(octopuses))
+        Ok(Self {
+            octopuses: Grid::parse_digits(input, "energy")?,
+        })
     }
 }
 
@@ -86,9 +53,29 @@ pub fn part_1(mut map: Cavern) -> usize {
 
 #[allow(clippy::maybe_infinite_iter)]
 pub fn part_2(mut map: Cavern) -> usize {
-    (1..)
-        .find(|_| map.tick() == map.width * map.height)
-        .unwrap()
+    (1..).find(|_| map.tick() == map.octopuses.len()).unwrap()
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Cavern;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::{parse_part, split_once, try_parse_str, Error, Solution};
 use std::collections::HashMap;
 use std::str::FromStr;
 
@@ -14,10 +15,7 @@ impl CaveSystem {
 
     fn insert_single(&mut self, from: Cave, to: Cave) {
         if from != Cave::End && to != Cave::Start {
-            self.connections
-                .entry(from)
-                .or_insert_with(Vec::new)
-                .push(to);
+            self.connections.entry(from).or_default().push(to);
         }
     }
 
@@ -94,10 +92,11 @@ impl<'a> Path<'a> {
 }
 
 impl FromStr for Cave {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         Ok(match input {
+            "" => return Err(Error::new("empty cave name")),
             "start" => Self::Start,
             "end" => Self::End,
             _ if input.to_lowercase() == input => Self::Small(input.to_owned()),
@@ -107,13 +106,13 @@ impl FromStr for Cave {
 }
 
 impl FromStr for CaveSystem {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut system = Self::default();
         for line in input.lines() {
-            let (a, b) = line.split_once('-').unwrap();
-            system.insert_connection(a.parse().unwrap(), b.parse().unwrap());
+            let (a, b) = split_once(line, "-").map_err(|err| err.within(input, line))?;
+            system.insert_connection(parse_part(input, a)?, parse_part(input, b)?);
         }
         Ok(system)
     }
@@ -127,6 +126,28 @@ pub fn part_2(system: &CaveSystem) -> usize {
     system.count_paths(true)
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = CaveSystem;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(&input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
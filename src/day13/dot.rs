@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use super::Fold;
+use crate::{parse_part, split_once, Error};
 
 #[derive(Default, Clone, Eq, PartialEq, Hash, Debug, Copy)]
 pub struct Dot {
@@ -25,13 +26,13 @@ impl Dot {
 }
 
 impl FromStr for Dot {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (x, y) = input.split_once(',').unwrap();
+        let (x, y) = split_once(input, ",")?;
         Ok(Self {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
+            x: parse_part(input, x)?,
+            y: parse_part(input, y)?,
         })
     }
 }
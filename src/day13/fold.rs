@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+use crate::{parse_part, split_once, Error};
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug, Copy)]
 pub enum Fold {
     X(u16),
@@ -7,16 +9,19 @@ pub enum Fold {
 }
 
 impl FromStr for Fold {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let n = input.split_once('=').unwrap().1.parse().unwrap();
-        Ok(if input.starts_with("fold along x") {
-            Self::X(n)
-        } else if input.starts_with("fold along y") {
-            Self::Y(n)
-        } else {
-            panic!("Unknown fold instruction: {}", input)
-        })
+        let (axis, n) = split_once(input, "=")?;
+        let n = parse_part(input, n)?;
+        match axis {
+            "fold along x" => Ok(Self::X(n)),
+            "fold along y" => Ok(Self::Y(n)),
+            _ => Err(Error::at(
+                input,
+                axis,
+                "expected 'fold along x' or 'fold along y'",
+            )),
+        }
     }
 }
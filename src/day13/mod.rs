@@ -2,6 +2,7 @@ mod dot;
 mod fold;
 mod paper;
 
+use crate::{try_parse_str, Error, Solution};
 use dot::Dot;
 use fold::Fold;
 use paper::Paper;
@@ -13,9 +14,29 @@ pub fn part_1(mut paper: Paper) -> usize {
 
 pub fn part_2(mut paper: Paper) -> String {
     paper.do_instructions();
-    let paper = paper.to_string();
-    println!("{}", paper);
-    paper
+    paper.to_string()
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Paper;
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
 }
 
 #[cfg(test)]
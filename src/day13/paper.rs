@@ -4,6 +4,8 @@ use std::fmt;
 use std::fmt::{Formatter, Write};
 use std::str::FromStr;
 
+use crate::{parse_part, split_once, Error};
+
 #[derive(Default, Clone)]
 pub struct Paper {
     dots: HashSet<Dot>,
@@ -11,16 +13,19 @@ pub struct Paper {
 }
 
 impl FromStr for Paper {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (dots, instructions) = input.split_once("\n\n").unwrap();
+        let (dots, instructions) = split_once(input, "\n\n")?;
         Ok(Self {
-            dots: dots.lines().map(|line| line.parse().unwrap()).collect(),
+            dots: dots
+                .lines()
+                .map(|line| parse_part(input, line))
+                .collect::<Result<_, _>>()?,
             instructions: instructions
                 .lines()
-                .map(|line| line.parse().unwrap())
-                .collect(),
+                .map(|line| parse_part(input, line))
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::{parse_part, split_once, try_parse_str, Error, Solution};
 use std::collections::HashMap;
 use std::str::FromStr;
 
@@ -51,7 +52,7 @@ struct PairInsertion {
 }
 
 impl FromStr for Polymer {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let chars: Vec<char> = s.chars().collect();
@@ -59,7 +60,9 @@ impl FromStr for Polymer {
         for window in chars.windows(2) {
             *chains.entry((window[0], window[1])).or_insert(0) += 1;
         }
-        let last_char = *chars.last().unwrap();
+        let last_char = *chars
+            .last()
+            .ok_or_else(|| Error::new("empty polymer template"))?;
         Ok(Self {
             chains,
             last_element: last_char,
@@ -68,26 +71,33 @@ impl FromStr for Polymer {
 }
 
 impl FromStr for Manual {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (polymer, rules) = input.split_once("\n\n").unwrap();
+        let (polymer, rules) = split_once(input, "\n\n")?;
         Ok(Self {
-            polymer_template: polymer.parse().unwrap(),
-            rules: rules.lines().map(|line| line.parse().unwrap()).collect(),
+            polymer_template: parse_part(input, polymer)?,
+            rules: rules
+                .lines()
+                .map(|line| parse_part(input, line))
+                .collect::<Result<_, _>>()?,
         })
     }
 }
 
 impl FromStr for PairInsertion {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (pair, insertion) = input.split_once(" -> ").unwrap();
-        let mut pair = pair.chars();
-        let a = pair.next().unwrap();
-        let b = pair.next().unwrap();
-        let c = insertion.chars().next().unwrap();
+        let (pair, insertion) = split_once(input, " -> ")?;
+        let (a, b) = match pair.chars().collect::<Vec<_>>()[..] {
+            [a, b] => (a, b),
+            _ => return Err(Error::at(input, pair, "expected a pair of elements")),
+        };
+        let c = match insertion.chars().collect::<Vec<_>>()[..] {
+            [c] => c,
+            _ => return Err(Error::at(input, insertion, "expected a single element")),
+        };
         Ok(Self {
             from: (a, b),
             to: ((a, c), (c, b)),
@@ -109,6 +119,28 @@ pub fn part_2(manual: &Manual) -> u64 {
     counts.values().max().unwrap() - counts.values().min().unwrap()
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Manual;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(&input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(&input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
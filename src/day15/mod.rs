@@ -1,145 +1,122 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use crate::search::dijkstra_dense;
+use crate::{try_parse_str, Error, Grid, Solution};
+use std::fmt;
 use std::str::FromStr;
 
-type Position = (usize, usize);
-
-#[derive(Clone, Debug, Copy, Hash)]
-struct Node {
-    risk_level: usize,
-    total_risk: usize,
-}
+pub type Position = (usize, usize);
 
 #[derive(Clone, Debug)]
 pub struct Cavern {
-    nodes: Vec<Vec<Node>>,
-    width: usize,
-    height: usize,
+    risk_levels: Grid<usize>,
 }
 
-#[derive(Clone, Debug, Copy, Hash, Eq, PartialEq)]
-struct Path {
-    position: Position,
-    total_risk: usize,
-}
-
-impl Ord for Path {
-    /// Path B > A when `B.total_risk` < `A.total_risk` because a lower risk is better
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.total_risk.cmp(&self.total_risk)
+impl Cavern {
+    fn is_goal(&self, position: Position) -> bool {
+        position == (self.risk_levels.width() - 1, self.risk_levels.height() - 1)
     }
-}
 
-impl PartialOrd for Path {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Path with the lowest total risk from the top left to the bottom right
+    pub fn solve(&self) -> Route<'_> {
+        let risk_levels = &self.risk_levels;
+        let width = risk_levels.width();
+        let found = dijkstra_dense(
+            (0, 0),
+            |&(x, y)| y * width + x,
+            |&position| {
+                risk_levels
+                    .neighbours(position)
+                    .map(move |next| (next, risk_levels[next]))
+            },
+            |&position| self.is_goal(position),
+        )
+        .expect("No solution found");
+        Route {
+            cavern: self,
+            positions: found.path,
+            total_risk: found.cost,
+        }
     }
 }
 
-impl Cavern {
-    fn neighbours(&self, (x, y): Position) -> impl Iterator<Item = Position> + 'static {
-        let up = if y > 0 { Some((x, y - 1)) } else { None };
-        let right = if x < self.width - 1 {
-            Some((x + 1, y))
-        } else {
-            None
-        };
-        let down = if y < self.height - 1 {
-            Some((x, y + 1))
-        } else {
-            None
-        };
-        let left = if x > 0 { Some((x - 1, y)) } else { None };
-        [up, right, down, left].into_iter().flatten()
-    }
-
-    fn is_goal(&self, position: Position) -> bool {
-        position == (self.width - 1, self.height - 1)
-    }
+/// A path through a [`Cavern`], displayed as the cavern with only the risk levels on the route.
+#[derive(Clone, Debug)]
+pub struct Route<'a> {
+    cavern: &'a Cavern,
+    /// From the top left to the bottom right, both included
+    pub positions: Vec<Position>,
+    /// Sum of the risk levels entered, so excluding the start
+    pub total_risk: usize,
+}
 
-    pub fn solve(&mut self) -> usize {
-        self.nodes[0][0].total_risk = 0;
-        let mut paths = BinaryHeap::new();
-        paths.push(Path {
-            position: (0, 0),
-            total_risk: 0,
-        });
-        while let Some(path) = paths.pop() {
-            if self.is_goal(path.position) {
-                return path.total_risk;
-            }
-            for position @ (x, y) in self.neighbours(path.position) {
-                let node = &mut self.nodes[y][x];
-                let total_risk = path.total_risk + node.risk_level;
-                if total_risk < node.total_risk {
-                    node.total_risk = total_risk;
-                    paths.push(Path {
-                        position,
-                        total_risk,
-                    });
-                }
-            }
+impl fmt::Display for Route<'_> {
+    #[allow(clippy::cast_possible_truncation)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let risk_levels = &self.cavern.risk_levels;
+        let mut map = risk_levels.map(|_| '.');
+        for &position in &self.positions {
+            map[position] = char::from(b'0' + risk_levels[position] as u8);
         }
-        panic!("No solution found");
+        write!(f, "{}", map)
     }
 }
 
 impl Cavern {
+    /// The cavern tiled `size` times in both directions, risk levels increasing with each tile
     pub fn new(input: CavernInput, size: usize) -> Self {
-        let risk_levels = input.risk_levels;
-        let input_height = risk_levels.len();
-        let input_width = risk_levels[0].len();
-        let height = input_height * size;
-        let width = input_width * size;
-        let risk = |x: usize, y: usize| {
+        let input = input.risk_levels;
+        let input_width = input.width();
+        let input_height = input.height();
+        let risk_levels = Grid::from_fn(input_width * size, input_height * size, |(x, y)| {
             let extra = x / input_width + y / input_height;
-            (risk_levels[y % input_height][x % input_width] + extra - 1) % 9 + 1
-        };
-        let nodes: Vec<Vec<Node>> = (0..height)
-            .map(|y| {
-                (0..width)
-                    .map(|x| Node {
-                        risk_level: risk(x, y),
-                        total_risk: usize::MAX,
-                    })
-                    .collect()
-            })
-            .collect();
-        Self {
-            nodes,
-            width,
-            height,
-        }
+            (input[(x % input_width, y % input_height)] + extra - 1) % 9 + 1
+        });
+        Self { risk_levels }
     }
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct CavernInput {
-    risk_levels: Vec<Vec<usize>>,
+    risk_levels: Grid<usize>,
 }
 
 impl FromStr for CavernInput {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let risk_levels = input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as usize)
-                    .collect()
-            })
-            .collect();
-        Ok(Self { risk_levels })
+        Ok(Self {
+            risk_levels: Grid::parse_digits(input, "risk level")?,
+        })
     }
 }
 
 pub fn part_1(input: CavernInput) -> usize {
-    Cavern::new(input, 1).solve()
+    Cavern::new(input, 1).solve().total_risk
 }
 
 pub fn part_2(input: CavernInput) -> usize {
-    Cavern::new(input, 5).solve()
+    Cavern::new(input, 5).solve().total_risk
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = CavernInput;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +156,29 @@ mod tests {
         assert_eq!(315, part_2(input));
     }
 
+    #[test]
+    fn example_route_enters_the_risk_it_sums() {
+        let cavern = Cavern::new(EXAMPLE.parse().unwrap(), 5);
+        let route = cavern.solve();
+        assert_eq!(Some(&(0, 0)), route.positions.first());
+        assert_eq!(Some(&(49, 49)), route.positions.last());
+        let entered: usize = route.positions[1..]
+            .iter()
+            .map(|&position| cavern.risk_levels[position])
+            .sum();
+        assert_eq!(315, entered);
+        assert!(route.positions.windows(2).all(|step| {
+            let ((x1, y1), (x2, y2)) = (step[0], step[1]);
+            x1.abs_diff(x2) + y1.abs_diff(y2) == 1
+        }));
+    }
+
+    #[test]
+    fn route_is_overlaid_on_the_cavern() {
+        let cavern = Cavern::new("19\n11".parse().unwrap(), 1);
+        assert_eq!("1.\n11\n", cavern.solve().to_string());
+    }
+
     #[test]
     fn part_2_works() {
         let input = parse_file("src/day15/input.txt");
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Unsigned integer of any size, for evaluating packets whose values do not fit in a `u64`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, without trailing zeros
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    fn normalize(mut self) -> Self {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+
+    /// Divides in place, returning the remainder
+    fn div_rem_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0;
+        for digit in self.digits.iter_mut().rev() {
+            let value = (remainder << 32) | u64::from(*digit);
+            *digit = (value / u64::from(divisor)) as u32;
+            remainder = value % u64::from(divisor);
+        }
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        remainder as u32
+    }
+
+    fn digit(&self, index: usize) -> u32 {
+        self.digits.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// By reference, so neither operand is cloned
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: Self) -> Self::Output {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for index in 0..len {
+            let sum = u64::from(self.digit(index)) + u64::from(other.digit(index)) + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        BigUint { digits }.normalize()
+    }
+}
+
+impl Add for BigUint {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+/// By reference, so neither operand is cloned
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut digits = vec![0; self.digits.len() + other.digits.len()];
+        for (i, &left) in self.digits.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &right) in other.digits.iter().enumerate() {
+                let product = u64::from(left) * u64::from(right) + u64::from(digits[i + j]) + carry;
+                digits[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        BigUint { digits }.normalize()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self {
+            digits: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalize()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // groups of 9 decimal digits, least significant first
+        const GROUP: u32 = 1_000_000_000;
+        let mut rest = self.clone();
+        let mut groups = Vec::new();
+        loop {
+            groups.push(rest.div_rem_small(GROUP));
+            if rest.digits.is_empty() {
+                break;
+            }
+        }
+        let mut groups = groups.iter().rev();
+        write!(f, "{}", groups.next().unwrap())?;
+        for group in groups {
+            write!(f, "{:09}", group)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_carries_between_digits() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!("18446744073709551615", max.to_string());
+        assert_eq!("36893488147419103230", (&max + &max).to_string());
+        assert_eq!(
+            "340282366920938463426481119284349108225",
+            (&max * &max).to_string()
+        );
+        assert_eq!("0", (BigUint::from(0) * max.clone()).to_string());
+        assert_eq!("1000000000", BigUint::from(1_000_000_000).to_string());
+    }
+
+    #[test]
+    fn orders_by_value() {
+        let max = BigUint::from(u64::MAX);
+        assert!(BigUint::from(3) < BigUint::from(5));
+        assert!(max < &max + &BigUint::from(1));
+        assert!(BigUint::from(1 << 40) > BigUint::from(u64::from(u32::MAX)));
+        assert_eq!(BigUint::from(0), BigUint::default());
+    }
+}
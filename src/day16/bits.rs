@@ -0,0 +1,363 @@
+use std::fmt;
+use std::io::{ErrorKind, Read};
+use std::str::FromStr;
+
+use super::error::{DecodeError, DecodeErrorKind};
+use crate::Error;
+
+/// A transmission, packed 8 bits to a byte with the first bit in the most significant one.
+///
+/// Bits past the end of the last byte are always zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bits {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bits {
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends the lowest `n` bits of `value`, most significant first, `n` is at most 64
+    pub fn push(&mut self, value: u64, n: usize) {
+        debug_assert!(n <= 64);
+        let mut n = n;
+        while n > 0 {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            // as many bits as fit in the last byte
+            let free = 8 - self.len % 8;
+            let count = free.min(n);
+            let chunk = (value >> (n - count)) & ((1 << count) - 1);
+            *self.bytes.last_mut().unwrap() |= (chunk << (free - count)) as u8;
+            self.len += count;
+            n -= count;
+        }
+    }
+
+    pub fn extend_from(&mut self, other: &Bits) {
+        let mut reader = Reader::new(other);
+        while !reader.is_empty() {
+            let n = reader.remaining().min(64);
+            self.push(reader.read(n).unwrap(), n);
+        }
+    }
+
+    /// Reads hex digits until the end of `reader`, which may only be followed by whitespace.
+    ///
+    /// Errors are placed on line 1 at the column of the offending character.
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, Error> {
+        let mut bits = Self::default();
+        let mut buffer = [0; 4096];
+        let mut column = 0;
+        let mut whitespace = None;
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => return Ok(bits),
+                Ok(read) => read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            for (index, &c) in buffer[..read].iter().enumerate() {
+                // columns count characters, so not the continuation bytes of UTF-8 sequences
+                if c & 0b1100_0000 != 0b1000_0000 {
+                    column += 1;
+                }
+                if c.is_ascii_whitespace() {
+                    whitespace.get_or_insert(column);
+                    continue;
+                }
+                let error = match whitespace {
+                    Some(_) => Some("unexpected hex digit after whitespace"),
+                    None if !bits.push_hex_digit(c) => Some("invalid hex digit"),
+                    None => None,
+                };
+                if let Some(message) = error {
+                    // the whole character, unless the buffer ends in the middle of it
+                    let rest = String::from_utf8_lossy(&buffer[index..read]);
+                    let c = rest.chars().next().unwrap();
+                    return Err(Error {
+                        line: Some(1),
+                        column: Some(column),
+                        ..Error::new(format!("{} '{}'", message, c))
+                    });
+                }
+            }
+        }
+    }
+
+    /// False when `c` is not a hex digit
+    fn push_hex_digit(&mut self, c: u8) -> bool {
+        let digit = match char::from(c).to_digit(16) {
+            Some(digit) => digit as u8,
+            None => return false,
+        };
+        // a digit after whole digits starts a byte or fills its second half
+        match self.len % 8 {
+            0 => {
+                self.bytes.push(digit << 4);
+                self.len += 4;
+            }
+            4 => {
+                *self.bytes.last_mut().unwrap() |= digit;
+                self.len += 4;
+            }
+            _ => self.push(u64::from(digit), 4),
+        }
+        true
+    }
+}
+
+impl FromIterator<bool> for Bits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Self::default();
+        for bit in iter {
+            bits.push(u64::from(bit), 1);
+        }
+        bits
+    }
+}
+
+/// Upper or lower case hex digits, an odd number of them ends with half a byte
+impl FromStr for Bits {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut bits = Self {
+            bytes: Vec::with_capacity(input.len().div_ceil(2)),
+            len: 0,
+        };
+        let invalid = |index: usize| {
+            // the first byte that is not a hex digit starts a character
+            let c = input[index..].chars().next().unwrap();
+            Error::at(input, &input[index..], format!("invalid hex digit '{}'", c))
+        };
+        let digit = |c: u8| char::from(c).to_digit(16).map(|digit| digit as u8);
+        let mut pairs = input.as_bytes().chunks_exact(2);
+        for (index, pair) in pairs.by_ref().enumerate() {
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(high), Some(low)) => bits.bytes.push(high << 4 | low),
+                (None, _) => return Err(invalid(2 * index)),
+                (Some(_), None) => return Err(invalid(2 * index + 1)),
+            }
+        }
+        bits.len = 4 * (input.len() - pairs.remainder().len());
+        if let [c] = *pairs.remainder() {
+            if !bits.push_hex_digit(c) {
+                return Err(invalid(input.len() - 1));
+            }
+        }
+        Ok(bits)
+    }
+}
+
+/// Upper case hex, padded with zeros to a whole number of bytes
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.bytes {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! parse {
+    ($uint:ident) => {
+        /// The next `n` bits, `n` must fit the type
+        pub(super) fn $uint(&mut self, n: usize) -> Result<$uint, DecodeError> {
+            debug_assert!(n <= $uint::BITS as usize);
+            Ok(self.read(n)? as $uint)
+        }
+    };
+}
+
+/// Reads bits from the front of a transmission, failing instead of reading past its end.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub(super) struct Reader<'a> {
+    bytes: &'a [u8],
+    /// Position of the next bit in the whole transmission
+    pub(super) offset: usize,
+    end: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(bits: &'a Bits) -> Self {
+        Self {
+            bytes: &bits.bytes,
+            offset: 0,
+            end: bits.len,
+        }
+    }
+
+    parse!(u8);
+    parse!(u64);
+    parse!(usize);
+
+    pub(super) fn bool(&mut self) -> Result<bool, DecodeError> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// The next `n` bits, at most 64, as the lowest bits of the result
+    #[inline]
+    pub(super) fn read(&mut self, n: usize) -> Result<u64, DecodeError> {
+        if n > self.remaining() {
+            return Err(self.truncated(n));
+        }
+        let first = self.offset / 8;
+        let skip = self.offset % 8;
+        let value = match self.bytes.get(first..first + 8) {
+            Some(bytes) if skip + n <= 64 && n > 0 => {
+                let window = u64::from_be_bytes(bytes.try_into().unwrap());
+                (window << skip) >> (64 - n)
+            }
+            _ => self.read_slow(n),
+        };
+        self.offset += n;
+        Ok(value)
+    }
+
+    /// Reads near the end of the bytes, or spanning 9 of them, without moving
+    #[cold]
+    fn read_slow(&self, n: usize) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        // the bits span at most 9 bytes, which fit in a u128 with room to spare
+        let first = self.offset / 8;
+        let last = (self.offset + n - 1) / 8;
+        let window = self.bytes[first..=last]
+            .iter()
+            .fold(0u128, |window, &byte| window << 8 | u128::from(byte));
+        let unused = 8 * (last + 1) - (self.offset + n);
+        (window >> unused) as u64 & (u64::MAX >> (64 - n))
+    }
+
+    /// The next `n` bits as a reader of their own
+    pub(super) fn take(&mut self, n: usize) -> Result<Self, DecodeError> {
+        if n > self.remaining() {
+            return Err(self.truncated(n));
+        }
+        let sub = Self {
+            end: self.offset + n,
+            ..*self
+        };
+        self.offset += n;
+        Ok(sub)
+    }
+
+    /// Position of the next bit that is set
+    pub(super) fn first_set(mut self) -> Option<usize> {
+        while !self.is_empty() {
+            let n = self.remaining().min(64);
+            let offset = self.offset;
+            let value = self.read(n).unwrap();
+            if value != 0 {
+                return Some(offset + value.leading_zeros() as usize - (64 - n));
+            }
+        }
+        None
+    }
+
+    pub(super) const fn remaining(self) -> usize {
+        self.end - self.offset
+    }
+
+    pub(super) const fn is_empty(self) -> bool {
+        self.offset == self.end
+    }
+
+    #[cold]
+    fn truncated(&self, n: usize) -> DecodeError {
+        DecodeError::new(
+            self.offset,
+            DecodeErrorKind::Truncated {
+                needed: n,
+                available: self.remaining(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_odd_length_and_lower_case_hex() {
+        let bits: Bits = "d2fE2".parse().unwrap();
+        assert_eq!(20, bits.len());
+        assert_eq!("D2FE20", bits.to_string());
+        let error = "C2 0".parse::<Bits>().unwrap_err();
+        assert_eq!("line 1, column 3: invalid hex digit ' '", error.to_string());
+        let error = "A\u{e9}".parse::<Bits>().unwrap_err();
+        assert_eq!(
+            "line 1, column 2: invalid hex digit '\u{e9}'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn reads_up_to_64_bits_at_any_offset() {
+        let bits: Bits = "0123456789ABCDEF01".parse().unwrap();
+        let mut reader = Reader::new(&bits);
+        assert_eq!(0, reader.read(4).unwrap());
+        assert_eq!(0x1234_5678_9ABC_DEF0, reader.read(64).unwrap());
+        assert_eq!(0b0, reader.read(3).unwrap());
+        assert_eq!(0b1, reader.read(1).unwrap());
+        let truncated = DecodeErrorKind::Truncated {
+            needed: 1,
+            available: 0,
+        };
+        assert_eq!(Err(DecodeError::new(72, truncated)), reader.read(1));
+        let mut reader = Reader::new(&bits);
+        reader.read(3).unwrap();
+        assert_eq!(0x091A_2B3C_4D5E_6F78, reader.read(64).unwrap());
+    }
+
+    #[test]
+    fn sub_readers_end_early() {
+        let bits: Bits = "FF00FF".parse().unwrap();
+        let mut reader = Reader::new(&bits);
+        reader.read(4).unwrap();
+        let mut sub = reader.take(8).unwrap();
+        assert_eq!(12, reader.offset);
+        assert_eq!(Some(16), reader.first_set());
+        assert_eq!(0b1111_0000, sub.read(8).unwrap());
+        assert!(sub.read(1).is_err());
+    }
+
+    #[test]
+    fn streams_from_a_reader() {
+        let hex = "0123456789abcdef".repeat(1000) + "\n";
+        let bits = Bits::from_reader(hex.as_bytes()).unwrap();
+        assert_eq!(64_000, bits.len());
+        assert_eq!(hex.trim().to_uppercase(), bits.to_string());
+        let error = Bits::from_reader("AB\nC".as_bytes()).unwrap_err();
+        assert_eq!(
+            "line 1, column 4: unexpected hex digit after whitespace 'C'",
+            error.to_string()
+        );
+        let error = Bits::from_reader("ABG".as_bytes()).unwrap_err();
+        assert_eq!("line 1, column 3: invalid hex digit 'G'", error.to_string());
+        let error = Bits::from_reader("AB\u{e9}".as_bytes()).unwrap_err();
+        assert_eq!(
+            "line 1, column 3: invalid hex digit '\u{e9}'",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn builds_from_bools() {
+        let bits: Bits = [true, false, true, true, false, false, false, false, true]
+            .into_iter()
+            .collect();
+        assert_eq!(9, bits.len());
+        assert_eq!("B080", bits.to_string());
+    }
+}
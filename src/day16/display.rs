@@ -0,0 +1,244 @@
+use std::fmt;
+
+use super::{Kind, LengthType, Operator, Packet};
+
+impl Kind {
+    /// Operators written between their operands, the others are written as functions
+    const fn infix(self) -> Option<&'static str> {
+        match self {
+            Self::Sum => Some("+"),
+            Self::Product => Some("*"),
+            Self::Minimum | Self::Maximum => None,
+            Self::GreaterThan => Some(">"),
+            Self::LessThan => Some("<"),
+            Self::EqualTo => Some("=="),
+        }
+    }
+
+    const fn function(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Minimum => "min",
+            Self::Maximum => "max",
+            Self::GreaterThan => "gt",
+            Self::LessThan => "lt",
+            Self::EqualTo => "eq",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Minimum => "minimum",
+            Self::Maximum => "maximum",
+            Self::GreaterThan => "greater than",
+            Self::LessThan => "less than",
+            Self::EqualTo => "equal to",
+        })
+    }
+}
+
+impl Packet {
+    /// How tightly the packet binds when written infix, operands binding less tightly need
+    /// parentheses
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Literal(_) => 4,
+            Self::Operator(operator) => match (operator.kind, &operator.packets[..]) {
+                (Kind::Sum | Kind::Product, [packet]) => packet.precedence(),
+                (Kind::Product, [_, _, ..]) => 3,
+                (Kind::Sum, [_, _, ..]) => 2,
+                (Kind::GreaterThan | Kind::LessThan | Kind::EqualTo, [_, _]) => 1,
+                // written as function calls
+                _ => 4,
+            },
+        }
+    }
+
+    /// Versions, bit offsets and length types of every packet, one per line
+    pub fn tree(&self) -> Tree<'_> {
+        Tree(self)
+    }
+}
+
+/// Expression the packet evaluates, like `max(3, 5 * 7) == 9`.
+///
+/// Sums and products of a single packet are written as that packet, comparisons that do not
+/// have two operands are written as functions like `eq(1)`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = match self {
+            Self::Literal(literal) => return write!(f, "{}", literal.value),
+            Self::Operator(operator) => operator,
+        };
+        let comparison = matches!(
+            operator.kind,
+            Kind::GreaterThan | Kind::LessThan | Kind::EqualTo
+        );
+        match (operator.kind.infix(), &operator.packets[..]) {
+            (Some(_), [packet]) if !comparison => write!(f, "{}", packet),
+            (Some(infix), packets @ [_, _, ..]) if !comparison || packets.len() == 2 => {
+                let precedence = self.precedence();
+                for (index, packet) in packets.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " {} ", infix)?;
+                    }
+                    // comparisons do not chain, so equal precedence needs parentheses too
+                    if packet.precedence() < precedence
+                        || (comparison && packet.precedence() == precedence)
+                    {
+                        write!(f, "({})", packet)?;
+                    } else {
+                        write!(f, "{}", packet)?;
+                    }
+                }
+                Ok(())
+            }
+            (_, packets) => {
+                write!(f, "{}(", operator.kind.function())?;
+                for (index, packet) in packets.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Tree dump of a [`Packet`], each packet on its own line indented below its operator.
+///
+/// Offsets are in bits from the start of the outermost packet, as laid out by
+/// [`Packet::encode`]. Decoding records every length type and literal group count, so for a
+/// decoded packet these are the offsets it was decoded at.
+pub struct Tree<'a>(&'a Packet);
+
+impl Tree<'_> {
+    fn write(
+        f: &mut fmt::Formatter<'_>,
+        packet: &Packet,
+        offset: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}bit {}: v{} ",
+            "",
+            offset,
+            packet.version(),
+            indent = 2 * depth
+        )?;
+        match packet {
+            Packet::Literal(literal) => writeln!(f, "literal {}", literal.value),
+            Packet::Operator(operator) => Self::write_operator(f, operator, offset, depth),
+        }
+    }
+
+    fn write_operator(
+        f: &mut fmt::Formatter<'_>,
+        operator: &Operator,
+        offset: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        let length_type = operator.encoded_length_type();
+        let mut offset = offset + 7 + length_type.field_size();
+        let length: usize = operator.packets.iter().map(Packet::encoded_len).sum();
+        match length_type {
+            LengthType::Bits => writeln!(
+                f,
+                "{}, {} packets in {} bits",
+                operator.kind,
+                operator.packets.len(),
+                length
+            )?,
+            LengthType::Count => {
+                writeln!(f, "{}, {} packets", operator.kind, operator.packets.len())?
+            }
+        }
+        for packet in &operator.packets {
+            Self::write(f, packet, offset, depth + 1)?;
+            offset += packet.encoded_len();
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Tree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::write(f, self.0, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(hex: &str) -> Packet {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn displays_examples_as_expressions() {
+        assert_eq!("1 + 2", decode("C200B40A82").to_string());
+        assert_eq!("6 * 9", decode("04005AC33890").to_string());
+        assert_eq!("min(7, 8, 9)", decode("880086C3E88112").to_string());
+        assert_eq!("max(7, 8, 9)", decode("CE00C43D881120").to_string());
+        assert_eq!("5 < 15", decode("D8005AC2A8F0").to_string());
+        assert_eq!(
+            "1 + 3 == 2 * 2",
+            decode("9C0141080250320F1802104A08").to_string()
+        );
+    }
+
+    #[test]
+    fn parentheses_only_where_needed() {
+        let literal = |value| Packet::literal(0, value);
+        let product = Packet::operator(0, Kind::Product, vec![literal(5), literal(7)]);
+        let max = Packet::operator(0, Kind::Maximum, vec![literal(3), product]);
+        let equal = Packet::operator(0, Kind::EqualTo, vec![max, literal(9)]);
+        assert_eq!("max(3, 5 * 7) == 9", equal.to_string());
+        let sum = Packet::operator(0, Kind::Sum, vec![literal(1), literal(2)]);
+        let product = Packet::operator(0, Kind::Product, vec![sum, literal(3)]);
+        assert_eq!("(1 + 2) * 3", product.to_string());
+        let nested = Packet::operator(0, Kind::LessThan, vec![equal, literal(1)]);
+        assert_eq!("(max(3, 5 * 7) == 9) < 1", nested.to_string());
+        let single = Packet::operator(0, Kind::Sum, vec![literal(4)]);
+        assert_eq!("4", single.to_string());
+    }
+
+    #[test]
+    fn tree_shows_versions_and_offsets() {
+        assert_eq!(
+            "bit 0: v1 less than, 2 packets in 27 bits\n  \
+            bit 22: v6 literal 10\n  \
+            bit 33: v2 literal 20\n",
+            decode("38006F45291200").tree().to_string()
+        );
+        assert_eq!(
+            "bit 0: v7 maximum, 3 packets\n  \
+            bit 18: v2 literal 1\n  \
+            bit 29: v4 literal 2\n  \
+            bit 40: v1 literal 3\n",
+            decode("EE00D40C823060").tree().to_string()
+        );
+    }
+
+    #[test]
+    fn tree_counts_leading_zero_groups() {
+        let padded = Packet::literal(6, 1).with_groups(3);
+        let sum = Packet::operator(0, Kind::Sum, vec![padded, Packet::literal(2, 2)]);
+        let hex = sum.to_hex().unwrap();
+        assert_eq!(
+            "bit 0: v0 sum, 2 packets\n  \
+            bit 18: v6 literal 1\n  \
+            bit 39: v2 literal 2\n",
+            decode(&hex).tree().to_string()
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use super::{Bits, Literal, Operator, Packet};
+use crate::Error;
+
+/// How an operator tells where its sub-packets end.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub enum LengthType {
+    /// Length type ID 0, the total number of bits in a 15 bit field
+    Bits,
+    /// Length type ID 1, the number of sub-packets in an 11 bit field
+    Count,
+}
+
+impl LengthType {
+    pub const fn field_size(self) -> usize {
+        match self {
+            Self::Bits => 15,
+            Self::Count => 11,
+        }
+    }
+}
+
+impl Bits {
+    /// Appends `value` in `n` bits, failing with "{what} N does not fit in n bits"
+    fn push_field(&mut self, what: &str, value: usize, n: usize) -> Result<(), Error> {
+        if value >> n != 0 {
+            return Err(Error::new(format!(
+                "{} {} does not fit in {} bits",
+                what, value, n
+            )));
+        }
+        self.push(value as u64, n);
+        Ok(())
+    }
+}
+
+impl Packet {
+    /// Fails when a version or sub-packet length does not fit its field
+    pub fn encode(&self) -> Result<Bits, Error> {
+        let mut bits = Bits::default();
+        self.write(&mut bits)?;
+        Ok(bits)
+    }
+
+    /// Hex transmission of the packet, padded with zeros to a whole number of bytes
+    pub fn to_hex(&self) -> Result<String, Error> {
+        Ok(self.encode()?.to_string())
+    }
+
+    /// Number of bits [`Packet::encode`] writes, without padding
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            Self::Literal(literal) => 6 + 5 * literal.encoded_groups(),
+            Self::Operator(operator) => {
+                let packets: usize = operator.packets.iter().map(Packet::encoded_len).sum();
+                7 + operator.encoded_length_type().field_size() + packets
+            }
+        }
+    }
+
+    fn write(&self, bits: &mut Bits) -> Result<(), Error> {
+        match self {
+            Self::Literal(literal) => literal.write(bits),
+            Self::Operator(operator) => operator.write(bits),
+        }
+    }
+}
+
+impl Literal {
+    /// Fewest 4 bit groups encoding the value, at least one even for zero
+    fn fewest_groups(&self) -> usize {
+        (64 - self.value.leading_zeros() as usize)
+            .max(1)
+            .div_ceil(4)
+    }
+
+    /// The number of groups, or the fewest when none was chosen
+    pub fn encoded_groups(&self) -> usize {
+        self.groups.unwrap_or_else(|| self.fewest_groups())
+    }
+
+    fn write(&self, bits: &mut Bits) -> Result<(), Error> {
+        bits.push_field("version", usize::from(self.version), 3)?;
+        bits.push(4, 3);
+        let groups = self.encoded_groups();
+        if groups < self.fewest_groups() {
+            return Err(Error::new(format!(
+                "value {} does not fit in {} groups",
+                self.value, groups
+            )));
+        }
+        for group in (0..groups).rev() {
+            bits.push(u64::from(group > 0), 1);
+            // groups past the 16th only hold leading zeros
+            let value = self.value.checked_shr(4 * group as u32).unwrap_or(0);
+            bits.push(value, 4);
+        }
+        Ok(())
+    }
+}
+
+impl Operator {
+    /// The length type, or the shortest one that fits when none was chosen
+    pub fn encoded_length_type(&self) -> LengthType {
+        // a count field is shorter, but can only hold up to 2047 sub-packets
+        let shortest = if self.packets.len() >> LengthType::Count.field_size() == 0 {
+            LengthType::Count
+        } else {
+            LengthType::Bits
+        };
+        self.length_type.unwrap_or(shortest)
+    }
+
+    fn write(&self, bits: &mut Bits) -> Result<(), Error> {
+        bits.push_field("version", usize::from(self.version), 3)?;
+        bits.push(u64::from(self.kind.type_id()), 3);
+        let mut packets = Bits::default();
+        for packet in &self.packets {
+            packet.write(&mut packets)?;
+        }
+        let count = self.packets.len();
+        match self.encoded_length_type() {
+            LengthType::Bits => {
+                bits.push(0, 1);
+                let size = LengthType::Bits.field_size();
+                bits.push_field("sub-packet length", packets.len(), size)?;
+            }
+            LengthType::Count => {
+                bits.push(1, 1);
+                let size = LengthType::Count.field_size();
+                bits.push_field("sub-packet count", count, size)?;
+            }
+        }
+        bits.extend_from(&packets);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file;
+
+    use super::super::Kind;
+    use super::*;
+
+    /// Deterministic pseudo random packet trees, as the crate has no random number dependency
+    struct Generator(u64);
+
+    impl Generator {
+        fn bits(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            self.0
+        }
+
+        fn next(&mut self, bound: u64) -> u64 {
+            (self.bits() >> 33) % bound
+        }
+
+        fn packet(&mut self, depth: usize) -> Packet {
+            let version = self.next(8) as u8;
+            if depth == 0 || self.next(3) == 0 {
+                let size = 1 + self.next(64);
+                let groups = size.div_ceil(4) + self.next(2);
+                return Packet::literal(version, self.bits() >> (64 - size))
+                    .with_groups(groups as usize);
+            }
+            let kind = Kind::ALL[self.next(7) as usize];
+            let count = if kind.type_id() >= 5 {
+                2
+            } else {
+                1 + self.next(4)
+            };
+            let packets = (0..count).map(|_| self.packet(depth - 1)).collect();
+            let length_type = if self.next(2) == 0 {
+                LengthType::Bits
+            } else {
+                LengthType::Count
+            };
+            Packet::operator(version, kind, packets).with_length_type(length_type)
+        }
+    }
+
+    #[test]
+    fn encodes_the_examples() {
+        assert_eq!("D2FE28", Packet::literal(6, 2021).to_hex().unwrap());
+        let operator = Packet::operator(
+            1,
+            Kind::LessThan,
+            vec![Packet::literal(6, 10), Packet::literal(2, 20)],
+        );
+        assert_eq!(
+            "38006F45291200",
+            operator
+                .clone()
+                .with_length_type(LengthType::Bits)
+                .to_hex()
+                .unwrap()
+        );
+        let operator = Packet::operator(
+            7,
+            Kind::Maximum,
+            vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3),
+            ],
+        );
+        assert_eq!("EE00D40C823060", operator.to_hex().unwrap());
+    }
+
+    #[test]
+    fn random_packets_round_trip() {
+        let mut generator = Generator(16);
+        for _ in 0..500 {
+            let packet = generator.packet(4);
+            let hex = packet.to_hex().unwrap();
+            let decoded = Packet::decode(&hex.parse().unwrap()).unwrap();
+            assert_eq!(packet, decoded, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn puzzle_input_round_trips() {
+        let bits: Bits = parse_file("src/day16/input.txt");
+        let packet = Packet::decode(&bits).unwrap();
+        assert_eq!(
+            Ok(&packet),
+            Packet::decode(&packet.encode().unwrap()).as_ref()
+        );
+        assert_eq!(packet.encoded_len(), packet.encode().unwrap().len());
+        assert!(bits.to_string().starts_with(&packet.to_hex().unwrap()));
+    }
+
+    #[test]
+    fn literals_keep_leading_zero_groups() {
+        // literal 1 in 2 groups
+        let bits: Bits = "D201".parse().unwrap();
+        let packet = Packet::decode(&bits).unwrap();
+        assert_eq!(Packet::literal(6, 1).with_groups(2), packet);
+        assert_eq!("D201", packet.to_hex().unwrap());
+        assert_eq!(16, packet.encoded_len());
+    }
+
+    #[test]
+    fn fields_that_do_not_fit_are_errors() {
+        let error = Packet::literal(8, 1).encode().unwrap_err();
+        assert_eq!("version 8 does not fit in 3 bits", error.to_string());
+        let error = Packet::literal(0, 16).with_groups(1).encode().unwrap_err();
+        assert_eq!("value 16 does not fit in 1 groups", error.to_string());
+        let packets = vec![Packet::literal(0, 0); 2048];
+        let error = Packet::operator(0, Kind::Sum, packets.clone())
+            .with_length_type(LengthType::Count)
+            .encode()
+            .unwrap_err();
+        assert_eq!(
+            "sub-packet count 2048 does not fit in 11 bits",
+            error.to_string()
+        );
+        let packets = Packet::operator(0, Kind::Sum, packets).encode().unwrap();
+        assert_eq!(
+            Some(LengthType::Bits),
+            match Packet::decode(&packets).unwrap() {
+                Packet::Operator(operator) => operator.length_type,
+                Packet::Literal(_) => None,
+            }
+        );
+    }
+}
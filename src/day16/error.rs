@@ -0,0 +1,145 @@
+use std::{error, fmt};
+
+use super::{Kind, Packet};
+
+/// Why a transmission could not be decoded, and at which bit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Bits from the start of the transmission
+    pub offset: usize,
+    pub kind: DecodeErrorKind,
+}
+
+/// Every 3 bit type id is either a literal or a [`Kind`], so there is no unknown type id error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The transmission, or the sub-packets of an operator, ended in the middle of a field
+    Truncated { needed: usize, available: usize },
+    /// A literal value does not fit in a `u64`, placed at the start of the literal packet
+    LiteralOverflow,
+    /// An operator cannot take that many sub-packets, placed at the start of its packet
+    OperandCount { kind: Kind, count: usize },
+    /// An operator is nested in [`Packet::MAX_DEPTH`] others, placed at the start of its packet
+    TooDeep,
+    /// A bit after the outermost packet is set
+    Padding,
+}
+
+impl DecodeError {
+    pub const fn new(offset: usize, kind: DecodeErrorKind) -> Self {
+        Self { offset, kind }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bit {}: ", self.offset)?;
+        match &self.kind {
+            DecodeErrorKind::Truncated { needed, available } => write!(
+                f,
+                "truncated transmission, expected {} more bits but found {}",
+                needed, available
+            ),
+            DecodeErrorKind::LiteralOverflow => f.write_str("literal does not fit in 64 bits"),
+            DecodeErrorKind::OperandCount { kind, count } => {
+                write!(f, "{} operator with {} sub-packets", kind, count)
+            }
+            DecodeErrorKind::TooDeep => {
+                write!(f, "more than {} nested operators", Packet::MAX_DEPTH)
+            }
+            DecodeErrorKind::Padding => f.write_str("padding after the packet is not zero"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Bits, LengthType};
+    use super::*;
+
+    fn decode(hex: &str) -> Result<Packet, DecodeError> {
+        Packet::decode(&hex.parse::<Bits>().unwrap())
+    }
+
+    #[test]
+    fn truncated_transmissions_are_errors() {
+        let truncated = DecodeErrorKind::Truncated {
+            needed: 5,
+            available: 0,
+        };
+        // literal 2021 missing its last group
+        assert_eq!(Err(DecodeError::new(16, truncated)), decode("D2FE"));
+        // operator claiming 27 bits of sub-packets with only 26 left
+        let truncated = DecodeErrorKind::Truncated {
+            needed: 27,
+            available: 26,
+        };
+        assert_eq!(Err(DecodeError::new(22, truncated)), decode("38006F452912"));
+    }
+
+    #[test]
+    fn literals_must_fit_in_64_bits() {
+        let fits = Packet::literal(0, u64::MAX).encode().unwrap();
+        assert!(Packet::decode(&fits).is_ok());
+        // 17 groups of 1111
+        let mut data = vec![false, false, false, true, false, false];
+        for group in 0..17 {
+            data.extend([group < 16, true, true, true, true]);
+        }
+        let error = Packet::decode(&data.into_iter().collect()).unwrap_err();
+        assert_eq!(DecodeError::new(0, DecodeErrorKind::LiteralOverflow), error);
+        assert_eq!("bit 0: literal does not fit in 64 bits", error.to_string());
+    }
+
+    #[test]
+    fn comparisons_need_two_sub_packets() {
+        let packets = vec![Packet::literal(0, 1); 3];
+        let hex = Packet::operator(1, Kind::EqualTo, packets)
+            .to_hex()
+            .unwrap();
+        let error = decode(&hex).unwrap_err();
+        assert_eq!(
+            "bit 0: equal to operator with 3 sub-packets",
+            error.to_string()
+        );
+        let hex = Packet::operator(1, Kind::Minimum, vec![]).to_hex().unwrap();
+        assert!(decode(&hex).is_err());
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| {
+            (0..depth).fold(Packet::literal(0, 1), |packet, _| {
+                Packet::operator(0, Kind::Sum, vec![packet]).with_length_type(LengthType::Count)
+            })
+        };
+        let fits = nested(Packet::MAX_DEPTH).encode().unwrap();
+        assert!(Packet::decode(&fits).is_ok());
+        let error = Packet::decode(&nested(Packet::MAX_DEPTH + 1).encode().unwrap()).unwrap_err();
+        // each operator header with its sub-packet count takes 18 bits
+        let offset = 18 * Packet::MAX_DEPTH;
+        assert_eq!(DecodeError::new(offset, DecodeErrorKind::TooDeep), error);
+        assert_eq!(
+            "bit 4608: more than 256 nested operators",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn padding_must_be_zero() {
+        assert!(decode("D2FE2800").is_ok());
+        let error = decode("D2FE29").unwrap_err();
+        assert_eq!(DecodeError::new(23, DecodeErrorKind::Padding), error);
+    }
+
+    #[test]
+    fn errors_point_at_the_hex_digit() {
+        let error = "D2FE29".parse::<Packet>().unwrap_err();
+        assert_eq!(
+            "line 1, column 6: bit 23: padding after the packet is not zero",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::{error, fmt};
+
+use super::{BigUint, Kind, Packet};
+
+/// Values packets can be evaluated as, with [`u64`] failing on overflow and [`BigUint`] exact.
+pub trait Number: Clone + Ord + From<u64> {
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+}
+
+impl Number for u64 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        u64::checked_add(*self, *other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        u64::checked_mul(*self, *other)
+    }
+}
+
+impl Number for BigUint {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+}
+
+/// Why a packet could not be evaluated, placed at the start of the failing operator as laid out
+/// by [`Packet::encode`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow {
+        offset: usize,
+        kind: Kind,
+    },
+    /// Only possible for built packets, decoding already rejects these
+    OperandCount {
+        offset: usize,
+        kind: Kind,
+        count: usize,
+    },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow { offset, kind } => write!(f, "bit {}: {} overflows", offset, kind),
+            Self::OperandCount {
+                offset,
+                kind,
+                count,
+            } => write!(
+                f,
+                "bit {}: {} operator with {} sub-packets",
+                offset, kind, count
+            ),
+        }
+    }
+}
+
+impl error::Error for EvalError {}
+
+impl Packet {
+    /// Like [`Packet::eval`], which is `eval_as::<u64>()`, and `eval_as::<BigUint>()` is exact
+    pub fn eval_as<N: Number>(&self) -> Result<N, EvalError> {
+        self.eval_at(0)
+    }
+
+    fn eval_at<N: Number>(&self, offset: usize) -> Result<N, EvalError> {
+        let operator = match self {
+            Self::Literal(literal) => return Ok(N::from(literal.value)),
+            Self::Operator(operator) => operator,
+        };
+        let kind = operator.kind;
+        let count = operator.packets.len();
+        if !kind.accepts(count) {
+            return Err(EvalError::OperandCount {
+                offset,
+                kind,
+                count,
+            });
+        }
+        let mut values = Vec::with_capacity(count);
+        let mut packet_offset = offset + 7 + operator.encoded_length_type().field_size();
+        for packet in &operator.packets {
+            values.push(packet.eval_at::<N>(packet_offset)?);
+            packet_offset += packet.encoded_len();
+        }
+        let overflow = || EvalError::Overflow { offset, kind };
+        let mut values = values.into_iter();
+        Ok(match kind {
+            Kind::Sum => values
+                .try_fold(N::from(0), |sum, value| sum.checked_add(&value))
+                .ok_or_else(overflow)?,
+            Kind::Product => values
+                .try_fold(N::from(1), |product, value| product.checked_mul(&value))
+                .ok_or_else(overflow)?,
+            Kind::Minimum => values.min().unwrap(),
+            Kind::Maximum => values.max().unwrap(),
+            Kind::GreaterThan | Kind::LessThan | Kind::EqualTo => {
+                let (left, right) = (values.next().unwrap(), values.next().unwrap());
+                let result = match kind {
+                    Kind::GreaterThan => left > right,
+                    Kind::LessThan => left < right,
+                    _ => left == right,
+                };
+                N::from(u64::from(result))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file;
+
+    use super::*;
+
+    #[test]
+    fn agrees_with_eval_when_nothing_overflows() {
+        let packet: Packet = parse_file("src/day16/input.txt");
+        let exact = packet.eval_as::<BigUint>().unwrap();
+        assert_eq!(packet.eval().unwrap().to_string(), exact.to_string());
+    }
+
+    #[test]
+    fn overflow_is_reported_at_the_operator() {
+        let large = Packet::literal(0, 1 << 40);
+        let product = Packet::operator(0, Kind::Product, vec![large.clone(), large.clone()]);
+        let sum = Packet::operator(0, Kind::Sum, vec![Packet::literal(0, 1), product]);
+        let error = sum.eval_as::<u64>().unwrap_err();
+        // the product follows the sum header and the literal 1
+        let offset = 7 + 11 + 11;
+        assert_eq!(
+            EvalError::Overflow {
+                offset,
+                kind: Kind::Product
+            },
+            error
+        );
+        assert_eq!("bit 29: product overflows", error.to_string());
+        assert_eq!(
+            "1208925819614629174706177",
+            sum.eval_as::<BigUint>().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn big_integers_compare_exactly() {
+        let huge = Packet::operator(
+            0,
+            Kind::Product,
+            vec![Packet::literal(0, u64::MAX), Packet::literal(0, 2)],
+        );
+        let larger = Packet::operator(0, Kind::Sum, vec![huge.clone(), Packet::literal(0, 1)]);
+        let greater = Packet::operator(0, Kind::GreaterThan, vec![larger, huge]);
+        assert_eq!(Ok(BigUint::from(1)), greater.eval_as::<BigUint>());
+        assert!(greater.eval_as::<u64>().is_err());
+    }
+
+    #[test]
+    fn built_packets_with_wrong_operand_counts_are_errors() {
+        let packet = Packet::operator(0, Kind::LessThan, vec![Packet::literal(0, 1)]);
+        assert_eq!(
+            "bit 0: less than operator with 1 sub-packets",
+            packet.eval_as::<u64>().unwrap_err().to_string()
+        );
+        let packet = Packet::operator(0, Kind::EqualTo, vec![]);
+        assert_eq!(
+            Err(EvalError::OperandCount {
+                offset: 0,
+                kind: Kind::EqualTo,
+                count: 0
+            }),
+            packet.eval()
+        );
+        let large = Packet::literal(0, u64::MAX);
+        let sum = Packet::operator(0, Kind::Sum, vec![large.clone(), large]);
+        assert!(sum.eval().is_err());
+    }
+}
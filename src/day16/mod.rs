@@ -1,203 +1,303 @@
-use std::fmt;
-use std::fmt::Formatter;
+use crate::{try_parse_str, Error, Solution};
+pub use big::BigUint;
+pub use bits::Bits;
+use bits::Reader;
+pub use display::Tree;
+pub use encoder::LengthType;
+pub use error::{DecodeError, DecodeErrorKind};
+pub use eval::{EvalError, Number};
 use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Bits {
-    data: Vec<bool>,
-}
+mod big;
+mod bits;
+mod display;
+mod encoder;
+mod error;
+mod eval;
 
-macro_rules! parse {
-    ($uint:ident) => {
-        fn $uint(&mut self, n: usize) -> $uint {
-            let mut value = 0;
-            for index in 0..n {
-                value <<= 1;
-                value |= self.buf[index] as $uint;
-            }
-            self.buf = &self.buf[n..];
-            value
-        }
-    };
+/// A decoded transmission, or one built to be encoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Packet {
+    Literal(Literal),
+    Operator(Operator),
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
-struct Reader<'a> {
-    buf: &'a [bool],
-}
+impl Packet {
+    pub const fn literal(version: u8, value: u64) -> Self {
+        Self::Literal(Literal {
+            version,
+            value,
+            groups: None,
+        })
+    }
 
-impl<'a> Reader<'a> {
-    fn new(bits: &'a Bits) -> Self {
-        Self { buf: &bits.data }
+    /// The length type is picked when encoding, see [`Packet::with_length_type`]
+    pub fn operator(version: u8, kind: Kind, packets: Vec<Packet>) -> Self {
+        Self::Operator(Operator {
+            version,
+            kind,
+            packets,
+            length_type: None,
+        })
     }
 
-    parse!(u8);
-    parse!(u64);
-    parse!(usize);
+    /// Encodes an operator's sub-packets with `length_type`, literals are returned unchanged
+    pub fn with_length_type(mut self, length_type: LengthType) -> Self {
+        if let Self::Operator(operator) = &mut self {
+            operator.length_type = Some(length_type);
+        }
+        self
+    }
 
-    fn bit(&mut self) -> u8 {
-        self.bool() as u8
+    /// Encodes a literal's value in `groups` 4 bit groups, operators are returned unchanged
+    pub fn with_groups(mut self, groups: usize) -> Self {
+        if let Self::Literal(literal) = &mut self {
+            literal.groups = Some(groups);
+        }
+        self
     }
 
-    fn bool(&mut self) -> bool {
-        let value = self.buf[0];
-        self.buf = &self.buf[1..];
-        value
+    /// Most operators a decoded packet can be nested in, so that decoding, encoding and
+    /// displaying a transmission can't run out of stack
+    pub const MAX_DEPTH: usize = 256;
+
+    /// The outermost packet of a transmission, the bits after it may only be zero padding
+    pub fn decode(bits: &Bits) -> Result<Self, DecodeError> {
+        let mut reader = Reader::new(bits);
+        let packet = Self::parse(&mut reader, 0)?;
+        if let Some(offset) = reader.first_set() {
+            return Err(DecodeError::new(offset, DecodeErrorKind::Padding));
+        }
+        Ok(packet)
     }
 
-    fn take(&mut self, n: usize) -> Self {
-        let result = Self {
-            buf: &self.buf[..n],
-        };
-        self.buf = &self.buf[n..];
-        result
+    /// Parses a packet nested in `depth` operators
+    fn parse(reader: &mut Reader, depth: usize) -> Result<Self, DecodeError> {
+        let offset = reader.offset;
+        let header = reader.u8(6)?;
+        let (version, type_id) = (header >> 3, header & 0b111);
+        Ok(match Kind::from_type_id(type_id) {
+            Some(_) if depth == Self::MAX_DEPTH => {
+                return Err(DecodeError::new(offset, DecodeErrorKind::TooDeep));
+            }
+            Some(kind) => Self::Operator(Operator::parse(offset, version, kind, reader, depth)?),
+            None => Self::Literal(Literal::parse(offset, version, reader)?),
+        })
     }
 
-    const fn len(self) -> usize {
-        self.buf.len()
+    pub const fn version(&self) -> u8 {
+        match self {
+            Self::Literal(literal) => literal.version,
+            Self::Operator(operator) => operator.version,
+        }
     }
-}
 
-impl<'a> fmt::Display for Reader<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        for bit in self.buf {
-            write!(f, "{}", bit)?;
+    /// Sum of the versions of this packet and all packets in it
+    pub fn version_sum(&self) -> u32 {
+        match self {
+            Self::Literal(literal) => literal.version.into(),
+            Self::Operator(operator) => operator.version_sum(),
         }
-        Ok(())
+    }
+
+    /// `u64` arithmetic, failing on overflow and on operators with the wrong number of
+    /// sub-packets, see [`Packet::eval_as`] to avoid overflow
+    pub fn eval(&self) -> Result<u64, EvalError> {
+        self.eval_as()
     }
 }
 
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct Literal {
+    pub version: u8,
+    pub value: u64,
+    /// How many 4 bit groups the value was or will be encoded in, `None` for the fewest
+    pub groups: Option<usize>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum Packet {
-    Literal(Literal),
-    Operator(Operator),
+pub struct Operator {
+    pub version: u8,
+    pub kind: Kind,
+    pub packets: Vec<Packet>,
+    /// How the sub-packets were or will be encoded, `None` to pick the shortest
+    pub length_type: Option<LengthType>,
 }
 
-impl Packet {
-    fn parse(reader: &mut Reader) -> Self {
-        let version = reader.u8(3);
-        let type_id = reader.u8(3);
+/// What an operator computes from its sub-packets, comparisons take exactly two.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Kind {
+    pub const ALL: [Self; 7] = [
+        Self::Sum,
+        Self::Product,
+        Self::Minimum,
+        Self::Maximum,
+        Self::GreaterThan,
+        Self::LessThan,
+        Self::EqualTo,
+    ];
+
+    /// `None` for 4, the type id of literals, and ids that do not fit in 3 bits
+    pub const fn from_type_id(type_id: u8) -> Option<Self> {
         match type_id {
-            4 => Self::Literal(Literal::parse(version, reader)),
-            _ => Self::Operator(Operator::parse(version, type_id, reader)),
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Minimum),
+            3 => Some(Self::Maximum),
+            5 => Some(Self::GreaterThan),
+            6 => Some(Self::LessThan),
+            7 => Some(Self::EqualTo),
+            _ => None,
         }
     }
 
-    fn version_sum(&self) -> u32 {
+    /// Whether an operator of this kind can have `count` sub-packets
+    pub const fn accepts(self, count: usize) -> bool {
         match self {
-            Self::Literal(literal) => literal.version.into(),
-            Self::Operator(operator) => operator.version_sum(),
+            Self::Sum | Self::Product => true,
+            Self::Minimum | Self::Maximum => count > 0,
+            Self::GreaterThan | Self::LessThan | Self::EqualTo => count == 2,
         }
     }
 
-    fn eval(&self) -> u64 {
+    pub const fn type_id(self) -> u8 {
         match self {
-            Self::Literal(literal) => literal.value as u64,
-            Self::Operator(operator) => operator.eval(),
+            Self::Sum => 0,
+            Self::Product => 1,
+            Self::Minimum => 2,
+            Self::Maximum => 3,
+            Self::GreaterThan => 5,
+            Self::LessThan => 6,
+            Self::EqualTo => 7,
         }
     }
 }
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq)]
-struct Literal {
-    version: u8,
-    value: u64,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct Operator {
-    version: u8,
-    packets: Vec<Packet>,
-    type_id: u8,
-}
-
 impl Literal {
-    fn parse(version: u8, reader: &mut Reader) -> Self {
-        let mut value = 0;
+    fn parse(offset: usize, version: u8, reader: &mut Reader) -> Result<Self, DecodeError> {
+        let mut value: u64 = 0;
+        let mut groups = 0;
         loop {
-            let prefix = reader.bool();
-            let v = reader.u64(4);
-            value <<= 4;
-            value |= v;
-            if !prefix {
-                return Self { version, value };
+            // a prefix bit telling whether more groups follow, then 4 bits of the value
+            let group = reader.u64(5)?;
+            groups += 1;
+            if value.leading_zeros() < 4 {
+                return Err(DecodeError::new(offset, DecodeErrorKind::LiteralOverflow));
+            }
+            value = value << 4 | (group & 0b1111);
+            if group & 0b1_0000 == 0 {
+                return Ok(Self {
+                    version,
+                    value,
+                    groups: Some(groups),
+                });
             }
         }
     }
 }
 
 impl Operator {
-    fn parse(version: u8, type_id: u8, reader: &mut Reader) -> Self {
-        let i = reader.bit();
+    fn parse(
+        offset: usize,
+        version: u8,
+        kind: Kind,
+        reader: &mut Reader,
+        depth: usize,
+    ) -> Result<Self, DecodeError> {
         let mut packets = Vec::new();
-        match i {
-            0 => {
-                let length = reader.usize(15);
-                let mut packet_reader = reader.take(length);
-                while packet_reader.len() >= 8 {
-                    let packet = Packet::parse(&mut packet_reader);
-                    packets.push(packet);
-                }
+        let length_type = if reader.bool()? {
+            let size = reader.usize(11)?;
+            for _ in 0..size {
+                packets.push(Packet::parse(reader, depth + 1)?);
             }
-            1 => {
-                let size = reader.usize(11);
-                for _ in 0..size {
-                    let packet = Packet::parse(reader);
-                    packets.push(packet);
-                }
+            LengthType::Count
+        } else {
+            let length = reader.usize(15)?;
+            let mut packet_reader = reader.take(length)?;
+            while !packet_reader.is_empty() {
+                packets.push(Packet::parse(&mut packet_reader, depth + 1)?);
             }
-            _ => unreachable!(),
+            LengthType::Bits
         };
-        Self {
+        if !kind.accepts(packets.len()) {
+            return Err(DecodeError::new(
+                offset,
+                DecodeErrorKind::OperandCount {
+                    kind,
+                    count: packets.len(),
+                },
+            ));
+        }
+        Ok(Self {
             version,
+            kind,
             packets,
-            type_id,
-        }
+            length_type: Some(length_type),
+        })
     }
 
     fn version_sum(&self) -> u32 {
         let packet_sum: u32 = self.packets.iter().map(Packet::version_sum).sum();
         u32::from(self.version) + packet_sum
     }
-
-    fn eval(&self) -> u64 {
-        let mut vals = self.packets.iter().map(Packet::eval);
-        match self.type_id {
-            0 => vals.sum(),
-            1 => vals.product(),
-            2 => vals.min().unwrap(),
-            3 => vals.max().unwrap(),
-            5 => (vals.next().unwrap() > vals.next().unwrap()) as u64,
-            6 => (vals.next().unwrap() < vals.next().unwrap()) as u64,
-            7 => (vals.next().unwrap() == vals.next().unwrap()) as u64,
-            _ => unreachable!(),
-        }
-    }
 }
 
-impl FromStr for Bits {
-    type Err = ();
+/// Hex transmission, errors from decoding are placed on the hex digit holding the failing bit
+impl FromStr for Packet {
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let data = (0..input.len())
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&input[i..i + 2], 16).unwrap())
-            .flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1 == 1))
-            .collect();
-        Ok(Self { data })
+        let bits: Bits = input.parse()?;
+        Self::decode(&bits).map_err(|err| Error {
+            line: Some(1),
+            column: Some(err.offset / 4 + 1),
+            ..Error::new(err.to_string())
+        })
     }
 }
 
-pub fn part_1(bits: &Bits) -> u32 {
-    let mut reader = Reader::new(bits);
-    let packet = Packet::parse(&mut reader);
+pub fn part_1(packet: &Packet) -> u32 {
     packet.version_sum()
 }
 
-pub fn part_2(bits: &Bits) -> u64 {
-    let mut reader = Reader::new(bits);
-    let packet = Packet::parse(&mut reader);
-    packet.eval()
+/// Exact, so no transmission can overflow. Panics on operators with the wrong number of
+/// sub-packets, which decoding rejects.
+pub fn part_2(packet: &Packet) -> BigUint {
+    packet
+        .eval_as()
+        .unwrap_or_else(|err: EvalError| panic!("{}", err))
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = Packet;
+    type Part1 = u32;
+    type Part2 = BigUint;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(&input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(&input)
+    }
 }
 
 #[cfg(test)]
@@ -208,25 +308,32 @@ mod tests {
 
     #[test]
     fn part_1_works() {
-        let input: Bits = parse_file("src/day16/input.txt");
+        let input: Packet = parse_file("src/day16/input.txt");
         assert_eq!(843, part_1(&input));
     }
 
     #[test]
     fn part_2_tests() {
-        assert_eq!(3, part_2(&"C200B40A82".parse().unwrap()));
-        assert_eq!(54, part_2(&"04005AC33890".parse().unwrap()));
-        assert_eq!(7, part_2(&"880086C3E88112".parse().unwrap()));
-        assert_eq!(9, part_2(&"CE00C43D881120".parse().unwrap()));
-        assert_eq!(1, part_2(&"D8005AC2A8F0".parse().unwrap()));
-        assert_eq!(0, part_2(&"F600BC2D8F".parse().unwrap()));
-        assert_eq!(0, part_2(&"9C005AC2F8F0".parse().unwrap()));
-        assert_eq!(1, part_2(&"9C0141080250320F1802104A08".parse().unwrap()));
+        let part_2 = |hex: &str| part_2(&hex.parse().unwrap());
+        assert_eq!(BigUint::from(3), part_2("C200B40A82"));
+        assert_eq!(BigUint::from(54), part_2("04005AC33890"));
+        assert_eq!(BigUint::from(7), part_2("880086C3E88112"));
+        assert_eq!(BigUint::from(9), part_2("CE00C43D881120"));
+        assert_eq!(BigUint::from(1), part_2("D8005AC2A8F0"));
+        assert_eq!(BigUint::from(0), part_2("F600BC2D8F"));
+        assert_eq!(BigUint::from(0), part_2("9C005AC2F8F0"));
+        assert_eq!(BigUint::from(1), part_2("9C0141080250320F1802104A08"));
     }
 
     #[test]
     fn part_2_works() {
-        let input: Bits = parse_file("src/day16/input.txt");
-        assert_eq!(5_390_807_940_351, part_2(&input));
+        let input: Packet = parse_file("src/day16/input.txt");
+        assert_eq!(BigUint::from(5_390_807_940_351), part_2(&input));
+    }
+
+    #[test]
+    fn invalid_hex_is_an_error() {
+        let error = "C200X40A82".parse::<Bits>().unwrap_err();
+        assert_eq!("line 1, column 5: invalid hex digit 'X'", error.to_string());
     }
 }
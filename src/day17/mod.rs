@@ -1,3 +1,4 @@
+use crate::{parse_range, split_once, try_parse_str, Error, Solution};
 use std::ops::{Add, AddAssign};
 use std::str::FromStr;
 
@@ -33,20 +34,21 @@ impl AddAssign for Vector {
 }
 
 impl FromStr for TargetArea {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let input = &input.trim()[13..];
-        let numbers: Vec<i32> = input
-            .split(", ")
-            .flat_map(|s| s[2..].split(".."))
-            .map(|s| s.parse().unwrap())
-            .collect();
+        let area = input
+            .trim()
+            .strip_prefix("target area: ")
+            .ok_or_else(|| Error::at(input, input, "expected 'target area: '"))?;
+        let (x, y) = split_once(area, ", ").map_err(|err| err.within(input, area))?;
+        let (x_min, x_max) = parse_range(input, x, "x=")?;
+        let (y_min, y_max) = parse_range(input, y, "y=")?;
         Ok(Self {
-            x_min: numbers[0],
-            x_max: numbers[1],
-            y_min: numbers[2],
-            y_max: numbers[3],
+            x_min,
+            x_max,
+            y_min,
+            y_max,
         })
     }
 }
@@ -90,6 +92,28 @@ pub fn part_2(target: TargetArea) -> usize {
     find_all_start_velocities(target).count()
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = TargetArea;
+    type Part1 = i32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
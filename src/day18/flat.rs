@@ -0,0 +1,220 @@
+use std::fmt;
+use std::ops::Add;
+
+use super::Snailfish;
+use crate::Error;
+
+/// Most regular numbers a sum of two reduced snailfish numbers can have
+const CAPACITY: usize = 32;
+/// Pairs nested deeper than this explode
+const MAX_DEPTH: u8 = 4;
+
+/// Snailfish number stored as its regular numbers from left to right, each with the number of
+/// pairs around it.
+///
+/// Only reduced numbers, nested at most 4 pairs deep, can be stored, so the sum of two never
+/// has more than 32 regular numbers and everything fits in fixed size arrays.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub struct FlatSnailfish {
+    values: [u32; CAPACITY],
+    depths: [u8; CAPACITY],
+    len: usize,
+}
+
+impl FlatSnailfish {
+    pub fn magnitude(&self) -> u32 {
+        // combine neighbours at the same depth until only the outermost value is left
+        let mut stack = [(0, 0); MAX_DEPTH as usize + 2];
+        let mut top = 0;
+        for (&value, &depth) in self.values().iter().zip(self.depths()) {
+            stack[top] = (value, depth);
+            top += 1;
+            while top >= 2 && stack[top - 1].1 == stack[top - 2].1 {
+                let (right, depth) = stack[top - 1];
+                let left = stack[top - 2].0;
+                stack[top - 2] = (3 * left + 2 * right, depth - 1);
+                top -= 1;
+            }
+        }
+        stack[0].0
+    }
+
+    fn values(&self) -> &[u32] {
+        &self.values[..self.len]
+    }
+
+    fn depths(&self) -> &[u8] {
+        &self.depths[..self.len]
+    }
+
+    fn reduce(&mut self) {
+        loop {
+            while let Some(index) = self.depths().iter().position(|&depth| depth > MAX_DEPTH) {
+                self.explode(index);
+            }
+            match self.values().iter().position(|&value| value >= 10) {
+                Some(index) => self.split(index),
+                None => return,
+            }
+        }
+    }
+
+    /// Explodes the pair of regular numbers starting at `index`
+    fn explode(&mut self, index: usize) {
+        let (left, right) = (self.values[index], self.values[index + 1]);
+        if index > 0 {
+            self.values[index - 1] += left;
+        }
+        if index + 2 < self.len {
+            self.values[index + 2] += right;
+        }
+        self.values[index] = 0;
+        self.depths[index] -= 1;
+        self.values.copy_within(index + 2..self.len, index + 1);
+        self.depths.copy_within(index + 2..self.len, index + 1);
+        self.len -= 1;
+    }
+
+    fn split(&mut self, index: usize) {
+        assert!(self.len < CAPACITY, "snailfish number too large");
+        let (value, depth) = (self.values[index], self.depths[index] + 1);
+        self.values.copy_within(index..self.len, index + 1);
+        self.depths.copy_within(index..self.len, index + 1);
+        self.len += 1;
+        self.values[index] = value / 2;
+        self.values[index + 1] = value - value / 2;
+        self.depths[index] = depth;
+        self.depths[index + 1] = depth;
+    }
+
+    fn push(&mut self, value: u32, depth: u8) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(format!(
+                "snailfish nested deeper than {} pairs",
+                MAX_DEPTH
+            )));
+        }
+        self.values[self.len] = value;
+        self.depths[self.len] = depth;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn push_all(&mut self, snailfish: &Snailfish, depth: u8) -> Result<(), Error> {
+        match snailfish {
+            Snailfish::Number(value) => self.push(*value, depth),
+            Snailfish::Pair(left, right) => {
+                self.push_all(left, depth + 1)?;
+                self.push_all(right, depth + 1)
+            }
+        }
+    }
+}
+
+impl Add for FlatSnailfish {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut result = Self {
+            len: self.len + rhs.len,
+            ..self
+        };
+        result.values[self.len..result.len].copy_from_slice(rhs.values());
+        result.depths[self.len..result.len].copy_from_slice(rhs.depths());
+        for depth in &mut result.depths[..result.len] {
+            *depth += 1;
+        }
+        result.reduce();
+        result
+    }
+}
+
+/// Fails when `snailfish` is nested deeper than a reduced number can be
+impl TryFrom<&Snailfish> for FlatSnailfish {
+    type Error = Error;
+
+    fn try_from(snailfish: &Snailfish) -> Result<Self, Self::Error> {
+        let mut flat = Self {
+            values: [0; CAPACITY],
+            depths: [0; CAPACITY],
+            len: 0,
+        };
+        flat.push_all(snailfish, 0)?;
+        Ok(flat)
+    }
+}
+
+impl From<&FlatSnailfish> for Snailfish {
+    fn from(flat: &FlatSnailfish) -> Self {
+        // the same stack as `magnitude`, building pairs instead
+        let mut stack: Vec<(Snailfish, u8)> = Vec::with_capacity(MAX_DEPTH as usize + 2);
+        for (&value, &depth) in flat.values().iter().zip(flat.depths()) {
+            stack.push((Snailfish::Number(value), depth));
+            while stack.len() >= 2 && stack[stack.len() - 1].1 == stack[stack.len() - 2].1 {
+                let (right, depth) = stack.pop().unwrap();
+                let (left, _) = stack.pop().unwrap();
+                stack.push((Snailfish::Pair(Box::new(left), Box::new(right)), depth - 1));
+            }
+        }
+        stack
+            .pop()
+            .map(|(snailfish, _)| snailfish)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for FlatSnailfish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Snailfish::from(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file_lines;
+
+    use super::*;
+
+    fn flat(input: &str) -> FlatSnailfish {
+        FlatSnailfish::try_from(&input.parse::<Snailfish>().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn converts_to_and_from_the_tree() {
+        let input = "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]";
+        assert_eq!(input, flat(input).to_string());
+        assert_eq!("7", flat("7").to_string());
+        let error = FlatSnailfish::try_from(&"[[[[[1,2],3],4],5],6]".parse().unwrap());
+        assert_eq!(
+            "snailfish nested deeper than 4 pairs",
+            error.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn addition_reduces() {
+        let sum = flat("[[[[4,3],4],4],[7,[[8,4],9]]]") + flat("[1,1]");
+        assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", sum.to_string());
+    }
+
+    #[test]
+    fn magnitude_matches_the_examples() {
+        assert_eq!(143, flat("[[1,2],[[3,4],5]]").magnitude());
+        assert_eq!(
+            3488,
+            flat("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]").magnitude()
+        );
+    }
+
+    #[test]
+    fn agrees_with_the_tree() {
+        let snailfish: Vec<Snailfish> = parse_file_lines("src/day18/input.txt").collect();
+        for pair in snailfish.windows(2) {
+            let tree = pair[0].clone() + pair[1].clone();
+            let flat = FlatSnailfish::try_from(&pair[0]).unwrap()
+                + FlatSnailfish::try_from(&pair[1]).unwrap();
+            assert_eq!(tree, Snailfish::from(&flat));
+            assert_eq!(tree.magnitude(), flat.magnitude());
+        }
+    }
+}
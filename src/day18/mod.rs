@@ -1,7 +1,16 @@
+use crate::{Error, Solution};
+pub use flat::FlatSnailfish;
+pub use parallel::{default_threads, largest_pair, LargestPair};
 use std::fmt;
 use std::fmt::Formatter;
 use std::ops::Add;
 use std::str::FromStr;
+pub use trace::{explain, Action, Explain, Reduction, Side, Step};
+
+mod flat;
+mod parallel;
+mod parser;
+mod trace;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Snailfish {
@@ -21,6 +30,10 @@ impl Add for Snailfish {
 
 impl Snailfish {
     fn reduce(&mut self) {
+        // each pass explodes the deepest pairs, sums of reduced numbers need a single one
+        for _ in 5..self.depth() {
+            self.explode(0);
+        }
         loop {
             self.explode(0);
             if !self.split() {
@@ -29,15 +42,16 @@ impl Snailfish {
         }
     }
 
+    /// Explodes every pair of regular numbers nested in 4 or more pairs, left to right
     fn explode(&mut self, level: usize) -> (Option<u32>, Option<u32>) {
-        match self {
-            Snailfish::Number(_) => (None, None),
-            Snailfish::Pair(a, b) if level == 4 => {
-                let a = a.unwrap_number();
-                let b = b.unwrap_number();
+        if level >= 4 {
+            if let Some((a, b)) = self.regular_pair() {
                 *self = Self::default();
-                (Some(a), Some(b))
+                return (Some(a), Some(b));
             }
+        }
+        match self {
+            Snailfish::Number(_) => (None, None),
             Snailfish::Pair(a, b) => {
                 let (left, mid_left) = a.explode(level + 1);
                 if let Some(x) = mid_left {
@@ -83,27 +97,26 @@ impl Snailfish {
         }
     }
 
-    fn unwrap_number(&self) -> u32 {
+    /// Both numbers of a pair of regular numbers
+    fn regular_pair(&self) -> Option<(u32, u32)> {
         match self {
-            Snailfish::Number(number) => *number,
-            Snailfish::Pair(_, _) => panic!("unwrap_number called on {:?}", self),
+            Snailfish::Pair(a, b) => match (a.as_ref(), b.as_ref()) {
+                (Snailfish::Number(a), Snailfish::Number(b)) => Some((*a, *b)),
+                _ => None,
+            },
+            Snailfish::Number(_) => None,
         }
     }
 
-    fn from_iterator(chars: &mut impl Iterator<Item = char>) -> Self {
-        let next = chars.next().unwrap();
-        if next == '[' {
-            let first = Self::from_iterator(chars);
-            assert_eq!(chars.next(), Some(','));
-            let second = Self::from_iterator(chars);
-            assert_eq!(chars.next(), Some(']'));
-            Self::Pair(Box::new(first), Box::new(second))
-        } else {
-            Self::Number(next.to_digit(10).unwrap())
+    /// Most pairs any regular number is nested in
+    fn depth(&self) -> usize {
+        match self {
+            Snailfish::Number(_) => 0,
+            Snailfish::Pair(a, b) => 1 + a.depth().max(b.depth()),
         }
     }
 
-    fn magnitude(&self) -> u32 {
+    pub fn magnitude(&self) -> u32 {
         match self {
             Snailfish::Number(nb) => *nb,
             Snailfish::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
@@ -132,31 +145,80 @@ impl fmt::Display for Snailfish {
     }
 }
 
+/// Multi-digit numbers and whitespace are accepted, so unreduced numbers parse too
 impl FromStr for Snailfish {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let chars = &mut input.chars();
-        let result = Self::from_iterator(chars);
-        assert_eq!(chars.next(), None);
-        Ok(result)
+        parser::parse(input)
     }
 }
 
-pub fn part_1(snailfish: impl Iterator<Item = Snailfish>) -> u32 {
-    snailfish.reduce(|a, b| a + b).unwrap().magnitude()
+/// The magnitude of `first + second`, added as [`FlatSnailfish`] unless either is nested deeper
+/// than 4 pairs
+fn sum_magnitude(first: &Snailfish, second: &Snailfish) -> u32 {
+    match (
+        FlatSnailfish::try_from(first),
+        FlatSnailfish::try_from(second),
+    ) {
+        (Ok(first), Ok(second)) => (first + second).magnitude(),
+        _ => (first.clone() + second.clone()).magnitude(),
+    }
 }
 
-pub fn part_2(snailfish: impl Iterator<Item = Snailfish>) -> u32 {
-    let snailfish: Vec<_> = snailfish.collect();
-    let len = snailfish.len();
-    (0..len)
-        .flat_map(|a| (0..len).map(move |b| (a, b)))
-        .filter(|(a, b)| a != b)
-        //TODO optimize this shit
-        .map(|(a, b)| ((&snailfish)[a].clone() + (&snailfish)[b].clone()).magnitude())
-        .max()
-        .unwrap()
+/// The magnitude of the sum of all numbers, `None` without any
+pub fn part_1(snailfish: impl Iterator<Item = Snailfish>) -> Option<u32> {
+    let snailfish: Vec<Snailfish> = snailfish.collect();
+    let flat: Result<Vec<FlatSnailfish>, Error> =
+        snailfish.iter().map(FlatSnailfish::try_from).collect();
+    match flat {
+        Ok(flat) => flat.into_iter().reduce(Add::add).map(|sum| sum.magnitude()),
+        // Numbers nested deeper than 4 pairs can only be added as trees
+        Err(_) => snailfish
+            .into_iter()
+            .reduce(Add::add)
+            .map(|sum| sum.magnitude()),
+    }
+}
+
+/// The largest magnitude of the sum of two different numbers, `None` with fewer than two
+pub fn part_2(snailfish: impl Iterator<Item = Snailfish>) -> Option<u32> {
+    let snailfish: Vec<Snailfish> = snailfish.collect();
+    largest_pair(&snailfish, default_threads()).map(|pair| pair.magnitude)
+}
+
+/// The answer, or a note that there is none
+fn answer(magnitude: Option<u32>, note: &str) -> String {
+    magnitude.map_or_else(|| note.to_string(), |magnitude| magnitude.to_string())
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = Vec<Snailfish>;
+    type Part1 = String;
+    type Part2 = String;
+
+    /// Homework numbers must be reduced, so none can be nested deeper than 4 pairs
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        input
+            .lines()
+            .map(|line| parser::parse_reduced(line).map_err(|err| err.within(input, line)))
+            .collect()
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        answer(part_1(input.into_iter()), "no snailfish numbers")
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        answer(
+            part_2(input.into_iter()),
+            "fewer than two snailfish numbers",
+        )
+    }
 }
 
 #[cfg(test)]
@@ -181,24 +243,60 @@ mod tests {
     #[test]
     fn example_1_produces_4140() {
         let snailfish = parse_str_lines(EXAMPLE);
-        assert_eq!(4140, part_1(snailfish));
+        assert_eq!(Some(4140), part_1(snailfish));
     }
 
     #[test]
     fn part_1_works() {
         let snailfish = parse_file_lines("src/day18/input.txt");
-        assert_eq!(3305, part_1(snailfish));
+        assert_eq!(Some(3305), part_1(snailfish));
     }
 
     #[test]
     fn example_2_produces_3993() {
         let snailfish = parse_str_lines(EXAMPLE);
-        assert_eq!(3993, part_2(snailfish));
+        assert_eq!(Some(3993), part_2(snailfish));
     }
 
     #[test]
     fn part_2_works() {
         let snailfish = parse_file_lines("src/day18/input.txt");
-        assert_eq!(4563, part_2(snailfish));
+        assert_eq!(Some(4563), part_2(snailfish));
+    }
+
+    #[test]
+    fn homework_nested_too_deep_is_an_error() {
+        let error = Day18::parse("[1,1]\n[[[[[1,2],3],4],5],6]").unwrap_err();
+        assert_eq!(
+            "line 2, column 5: snailfish nested deeper than 4 pairs",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn numbers_nested_too_deep_are_added_as_trees() {
+        let snailfish: Vec<Snailfish> = parse_str_lines("[1,1]\n[[[[[1,2],3],4],5],6]").collect();
+        let sum = snailfish[0].clone() + snailfish[1].clone();
+        assert_eq!(Some(sum.magnitude()), part_1(snailfish.into_iter()));
+        let snailfish = parse_str_lines("[1,1]\n[[[[[1,2],3],4],5],6]");
+        assert!(part_2(snailfish).is_some());
+    }
+
+    #[test]
+    fn too_few_numbers_have_no_answer() {
+        assert_eq!("no snailfish numbers", Day18::part_1(Vec::new()));
+        let input = Day18::parse("[1,2]").unwrap();
+        assert_eq!("fewer than two snailfish numbers", Day18::part_2(input));
+    }
+
+    #[test]
+    fn unbalanced_snailfish_is_an_error() {
+        let error = "[[1,2],3".parse::<Snailfish>().unwrap_err();
+        assert_eq!("line 1, column 9: expected ']'", error.to_string());
+        let error = "[[1,2];3]".parse::<Snailfish>().unwrap_err();
+        assert_eq!(
+            "line 1, column 7: expected ',', found ';'",
+            error.to_string()
+        );
     }
 }
@@ -0,0 +1,97 @@
+use std::cmp::Reverse;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use super::{sum_magnitude, Snailfish};
+
+/// Two different numbers of a homework list, by index, and the magnitude of their sum.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub struct LargestPair {
+    pub first: usize,
+    pub second: usize,
+    pub magnitude: u32,
+}
+
+impl LargestPair {
+    /// Larger magnitudes first, then the lowest indices so ties do not depend on threads
+    fn key(&self) -> (u32, Reverse<(usize, usize)>) {
+        (self.magnitude, Reverse((self.first, self.second)))
+    }
+}
+
+/// Threads to use when none are given, one per available core
+pub fn default_threads() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// The ordered pair of different numbers with the largest sum, `None` with fewer than two.
+///
+/// Each of `threads` threads takes every `threads`th first number and tries it with all the others.
+pub fn largest_pair(snailfish: &[Snailfish], threads: NonZeroUsize) -> Option<LargestPair> {
+    let threads = threads.get().min(snailfish.len().max(1));
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|start| scope.spawn(move || search(snailfish, start, threads)))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .max_by_key(LargestPair::key)
+    })
+}
+
+/// The best pair whose first index is `start` plus a multiple of `step`
+fn search(snailfish: &[Snailfish], start: usize, step: usize) -> Option<LargestPair> {
+    (start..snailfish.len())
+        .step_by(step)
+        .flat_map(|first| (0..snailfish.len()).map(move |second| (first, second)))
+        .filter(|(first, second)| first != second)
+        .map(|(first, second)| LargestPair {
+            first,
+            second,
+            magnitude: sum_magnitude(&snailfish[first], &snailfish[second]),
+        })
+        .max_by_key(LargestPair::key)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file_lines;
+
+    use super::*;
+
+    #[test]
+    fn any_number_of_threads_finds_the_same_pair() {
+        let snailfish: Vec<Snailfish> = parse_file_lines("src/day18/input.txt").collect();
+        let expected = largest_pair(&snailfish, NonZeroUsize::MIN).unwrap();
+        assert_eq!(4563, expected.magnitude);
+        let sum = snailfish[expected.first].clone() + snailfish[expected.second].clone();
+        assert_eq!(expected.magnitude, sum.magnitude());
+        for threads in [2, 3, 8, 1000] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            assert_eq!(Some(expected), largest_pair(&snailfish, threads));
+        }
+    }
+
+    #[test]
+    fn needs_two_numbers() {
+        let snailfish: Vec<Snailfish> = vec!["[1,2]".parse().unwrap()];
+        assert_eq!(None, largest_pair(&snailfish, default_threads()));
+        assert_eq!(None, largest_pair(&[], default_threads()));
+    }
+
+    #[test]
+    fn unreduced_numbers_are_added_as_trees() {
+        let snailfish: Vec<Snailfish> = ["[1,2]", "[[[[[1,2],3],4],5],6]"]
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        let sum = snailfish[1].clone() + snailfish[0].clone();
+        let expected = LargestPair {
+            first: 1,
+            second: 0,
+            magnitude: sum.magnitude(),
+        };
+        assert_eq!(Some(expected), largest_pair(&snailfish, default_threads()));
+    }
+}
@@ -0,0 +1,180 @@
+use super::Snailfish;
+use crate::Error;
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    /// One or more decimal digits
+    Number,
+    /// Any other character
+    Other,
+}
+
+/// Splits a snailfish number into tokens, each with its slice of the input, skipping whitespace.
+struct Lexer<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = (Token, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rest = self.rest.trim_start();
+        let c = self.rest.chars().next()?;
+        let (token, len) = match c {
+            '[' => (Token::Open, 1),
+            ']' => (Token::Close, 1),
+            ',' => (Token::Comma, 1),
+            '0'..='9' => {
+                let len = self
+                    .rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(self.rest.len());
+                (Token::Number, len)
+            }
+            _ => (Token::Other, c.len_utf8()),
+        };
+        let (text, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some((token, text))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    lexer: Lexer<'a>,
+    /// Most pairs a regular number can be nested in
+    max_depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a number nested in `depth` pairs
+    fn snailfish(&mut self, depth: usize) -> Result<Snailfish, Error> {
+        match self.lexer.next() {
+            Some((Token::Open, text)) if depth == self.max_depth => Err(Error::at(
+                self.input,
+                text,
+                format!("snailfish nested deeper than {} pairs", self.max_depth),
+            )),
+            Some((Token::Open, _)) => {
+                let first = self.snailfish(depth + 1)?;
+                self.expect(Token::Comma, ',')?;
+                let second = self.snailfish(depth + 1)?;
+                self.expect(Token::Close, ']')?;
+                Ok(Snailfish::Pair(Box::new(first), Box::new(second)))
+            }
+            Some((Token::Number, text)) => text.parse().map(Snailfish::Number).map_err(|_| {
+                Error::at(
+                    self.input,
+                    text,
+                    format!("{} does not fit in 32 bits", text),
+                )
+            }),
+            Some((_, text)) => Err(Error::at(
+                self.input,
+                text,
+                format!("expected a number or '[', found '{}'", text),
+            )),
+            None => Err(Error::at_end(self.input, "expected a number or '['")),
+        }
+    }
+
+    fn expect(&mut self, expected: Token, c: char) -> Result<(), Error> {
+        match self.lexer.next() {
+            Some((token, _)) if token == expected => Ok(()),
+            Some((_, text)) => Err(Error::at(
+                self.input,
+                text,
+                format!("expected '{}', found '{}'", c, text),
+            )),
+            None => Err(Error::at_end(self.input, format!("expected '{}'", c))),
+        }
+    }
+}
+
+/// Numbers of any size and nesting, with whitespace allowed between tokens
+pub(super) fn parse(input: &str) -> Result<Snailfish, Error> {
+    parse_nested(input, usize::MAX)
+}
+
+/// Like [`parse`], but rejects numbers nested deeper than a reduced number can be
+pub(super) fn parse_reduced(input: &str) -> Result<Snailfish, Error> {
+    parse_nested(input, 4)
+}
+
+fn parse_nested(input: &str, max_depth: usize) -> Result<Snailfish, Error> {
+    let mut parser = Parser {
+        input,
+        lexer: Lexer { rest: input },
+        max_depth,
+    };
+    let snailfish = parser.snailfish(0)?;
+    match parser.lexer.next() {
+        Some((_, text)) => Err(Error::at(input, text, "expected end of line")),
+        None => Ok(snailfish),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_multiple_digits_and_whitespace() {
+        let snailfish = parse(" [ [10, 2],\n\t345 ] ").unwrap();
+        assert_eq!("[[10,2],345]", snailfish.to_string());
+        assert_eq!(Snailfish::Number(0), parse("0").unwrap());
+    }
+
+    #[test]
+    fn round_trips_numbers_mid_reduction() {
+        let a = parse("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap();
+        for step in a.trace_add(parse("[1,1]").unwrap()) {
+            assert_eq!(step.result, parse(&step.result.to_string()).unwrap());
+        }
+    }
+
+    #[test]
+    fn reduced_numbers_are_at_most_4_pairs_deep() {
+        assert!(parse_reduced("[[[[1,2],3],4],5]").is_ok());
+        let error = parse_reduced("[[[[[1,2],3],4],5],6]").unwrap_err();
+        assert_eq!(
+            "line 1, column 5: snailfish nested deeper than 4 pairs",
+            error.to_string()
+        );
+        assert!(parse("[[[[[1,2],3],4],5],6]").is_ok());
+    }
+
+    #[test]
+    fn errors_are_positioned() {
+        let error = parse("[12a,3]").unwrap_err();
+        assert_eq!(
+            "line 1, column 4: expected ',', found 'a'",
+            error.to_string()
+        );
+        let error = parse("[1,\n  2 3]").unwrap_err();
+        assert_eq!(
+            "line 2, column 5: expected ']', found '3'",
+            error.to_string()
+        );
+        let error = parse("[1,99999999999]").unwrap_err();
+        assert_eq!(
+            "line 1, column 4: 99999999999 does not fit in 32 bits",
+            error.to_string()
+        );
+        let error = parse("[1,2] [3,4]").unwrap_err();
+        assert_eq!("line 1, column 7: expected end of line", error.to_string());
+        let error = parse("[,]").unwrap_err();
+        assert_eq!(
+            "line 1, column 2: expected a number or '[', found ','",
+            error.to_string()
+        );
+        let error = parse("  ").unwrap_err();
+        assert_eq!(
+            "line 1, column 3: expected a number or '['",
+            error.to_string()
+        );
+    }
+}
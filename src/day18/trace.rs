@@ -0,0 +1,288 @@
+use std::fmt;
+
+use super::Snailfish;
+
+/// Which element of a pair to descend into, a path starts at the outermost pair.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A single reduction action, at the path of the pair that exploded or the number that split.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Explode { path: Vec<Side>, pair: (u32, u32) },
+    Split { path: Vec<Side>, value: u32 },
+}
+
+impl Action {
+    pub fn path(&self) -> &[Side] {
+        match self {
+            Self::Explode { path, .. } | Self::Split { path, .. } => path,
+        }
+    }
+
+    /// "explode" or "split", as in the puzzle's worked example
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Explode { .. } => "explode",
+            Self::Split { .. } => "split",
+        }
+    }
+}
+
+/// Like `explode [4,3] at LLLL`, the path as one letter per side
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Explode { pair, .. } => write!(f, "explode [{},{}] at ", pair.0, pair.1)?,
+            Self::Split { value, .. } => write!(f, "split {} at ", value)?,
+        }
+        for side in self.path() {
+            let letter = match side {
+                Side::Left => 'L',
+                Side::Right => 'R',
+            };
+            write!(f, "{}", letter)?;
+        }
+        Ok(())
+    }
+}
+
+/// An action and the number right after it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Step {
+    pub action: Action,
+    pub result: Snailfish,
+}
+
+/// The steps reducing a number one action at a time, see [`Snailfish::reduction`].
+#[derive(Clone, Debug)]
+pub struct Reduction {
+    snailfish: Snailfish,
+}
+
+impl Reduction {
+    /// Runs the remaining steps and returns the reduced number
+    pub fn finish(mut self) -> Snailfish {
+        while self.snailfish.step().is_some() {}
+        self.snailfish
+    }
+}
+
+impl Iterator for Reduction {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = self.snailfish.step()?;
+        Some(Step {
+            action,
+            result: self.snailfish.clone(),
+        })
+    }
+}
+
+/// What exploding the leftmost pair carries outwards
+struct Explosion {
+    pair: (u32, u32),
+    left: Option<u32>,
+    right: Option<u32>,
+}
+
+impl Snailfish {
+    /// Reduces this number step by step, ends up where adding does in one go
+    pub fn reduction(self) -> Reduction {
+        Reduction { snailfish: self }
+    }
+
+    /// The reduction of the unreduced sum of `self` and `rhs`
+    pub fn trace_add(self, rhs: Self) -> Reduction {
+        Self::Pair(Box::new(self), Box::new(rhs)).reduction()
+    }
+
+    /// Explodes the leftmost pair nested in four others, or else splits the leftmost number
+    fn step(&mut self) -> Option<Action> {
+        let mut path = Vec::new();
+        if let Some(explosion) = self.explode_first(0, &mut path) {
+            return Some(Action::Explode {
+                path,
+                pair: explosion.pair,
+            });
+        }
+        let value = self.split_first(&mut path)?;
+        Some(Action::Split { path, value })
+    }
+
+    /// Explodes the leftmost pair of regular numbers nested in 4 or more pairs
+    fn explode_first(&mut self, level: usize, path: &mut Vec<Side>) -> Option<Explosion> {
+        if level >= 4 {
+            if let Some(pair) = self.regular_pair() {
+                *self = Self::default();
+                return Some(Explosion {
+                    pair,
+                    left: Some(pair.0),
+                    right: Some(pair.1),
+                });
+            }
+        }
+        match self {
+            Snailfish::Number(_) => None,
+            Snailfish::Pair(a, b) => {
+                path.push(Side::Left);
+                if let Some(mut explosion) = a.explode_first(level + 1, path) {
+                    if let Some(x) = explosion.right.take() {
+                        b.add_left(x);
+                    }
+                    return Some(explosion);
+                }
+                path.pop();
+                path.push(Side::Right);
+                if let Some(mut explosion) = b.explode_first(level + 1, path) {
+                    if let Some(x) = explosion.left.take() {
+                        a.add_right(x);
+                    }
+                    return Some(explosion);
+                }
+                path.pop();
+                None
+            }
+        }
+    }
+
+    /// The number that was split
+    fn split_first(&mut self, path: &mut Vec<Side>) -> Option<u32> {
+        match self {
+            Snailfish::Number(x) if *x >= 10 => {
+                let value = *x;
+                self.split();
+                Some(value)
+            }
+            Snailfish::Number(_) => None,
+            Snailfish::Pair(a, b) => {
+                for (side, snailfish) in [(Side::Left, a), (Side::Right, b)] {
+                    path.push(side);
+                    if let Some(value) = snailfish.split_first(path) {
+                        return Some(value);
+                    }
+                    path.pop();
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Prints every addition of a homework list with its reduction, like the puzzle's examples.
+///
+/// ```text
+///   [[[[4,3],4],4],[7,[[8,4],9]]]
+/// + [1,1]
+/// after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]
+/// after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]
+/// ...
+/// = [[[[0,7],4],[[7,8],[6,0]]],[8,1]]
+/// ```
+pub struct Explain<'a>(&'a [Snailfish]);
+
+/// See [`Explain`]
+pub fn explain(homework: &[Snailfish]) -> Explain<'_> {
+    Explain(homework)
+}
+
+impl fmt::Display for Explain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut numbers = self.0.iter();
+        let mut sum = match numbers.next() {
+            Some(first) => first.clone(),
+            None => return Ok(()),
+        };
+        for snailfish in numbers {
+            writeln!(f, "  {}", sum)?;
+            writeln!(f, "+ {}", snailfish)?;
+            let addition = Snailfish::Pair(Box::new(sum), Box::new(snailfish.clone()));
+            writeln!(f, "after addition: {}", addition)?;
+            let mut reduction = addition.reduction();
+            for step in reduction.by_ref() {
+                let name = format!("{}:", step.action.name());
+                writeln!(f, "after {:<10}{}", name, step.result)?;
+            }
+            sum = reduction.finish();
+            writeln!(f, "= {}", sum)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_file_lines, parse_str_lines};
+
+    use super::*;
+
+    #[test]
+    fn explains_the_worked_example() {
+        let homework: Vec<Snailfish> =
+            parse_str_lines("[[[[4,3],4],4],[7,[[8,4],9]]]\n[1,1]\n").collect();
+        let expected = "  [[[[4,3],4],4],[7,[[8,4],9]]]\n\
+                        + [1,1]\n\
+                        after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]\n\
+                        after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]\n\
+                        after explode:  [[[[0,7],4],[15,[0,13]]],[1,1]]\n\
+                        after split:    [[[[0,7],4],[[7,8],[0,13]]],[1,1]]\n\
+                        after split:    [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]\n\
+                        after explode:  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]\n\
+                        = [[[[0,7],4],[[7,8],[6,0]]],[8,1]]\n";
+        assert_eq!(expected, explain(&homework).to_string());
+    }
+
+    #[test]
+    fn steps_have_paths() {
+        let a: Snailfish = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+        let actions: Vec<String> = a
+            .trace_add("[1,1]".parse().unwrap())
+            .map(|step| step.action.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "explode [4,3] at LLLL",
+                "explode [8,4] at LRRL",
+                "split 15 at LRL",
+                "split 13 at LRRR",
+                "explode [6,7] at LRRR",
+            ],
+            actions
+        );
+    }
+
+    #[test]
+    fn agrees_with_addition() {
+        let snailfish: Vec<Snailfish> = parse_file_lines("src/day18/input.txt").collect();
+        for pair in snailfish.windows(2) {
+            let sum = pair[0].clone() + pair[1].clone();
+            let traced = pair[0].clone().trace_add(pair[1].clone());
+            assert_eq!(sum, traced.finish());
+        }
+    }
+
+    #[test]
+    fn explodes_numbers_nested_deeper_than_5_pairs() {
+        let a: Snailfish = "[[[[[[1,2],3],4],5],6],7]".parse().unwrap();
+        let actions: Vec<String> = a
+            .clone()
+            .reduction()
+            .map(|step| step.action.to_string())
+            .collect();
+        assert_eq!(
+            vec!["explode [1,2] at LLLLL", "explode [0,5] at LLLL"],
+            actions
+        );
+        assert_eq!(
+            "[[[[0,9],5],6],7]",
+            a.clone().reduction().finish().to_string()
+        );
+        let mut reduced = a;
+        reduced.reduce();
+        assert_eq!("[[[[0,9],5],6],7]", reduced.to_string());
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{HashSet, VecDeque};
 use std::str::FromStr;
 
+use crate::{parse_part, try_parse_str, Error, Solution};
 use vector::Vector;
 
 mod vector;
@@ -115,23 +116,29 @@ impl From<Vec<Vector>> for Scanner {
 }
 
 impl FromStr for Scanner {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let beacons: Vec<Vector> = input
-            .lines()
-            .skip(1)
-            .map(|line| line.parse().unwrap())
-            .collect();
+        let mut lines = input.lines();
+        match lines.next() {
+            Some(header) if header.starts_with("--- scanner") => {}
+            _ => return Err(Error::at(input, input, "expected '--- scanner'")),
+        }
+        let beacons: Vec<Vector> = lines
+            .map(|line| parse_part(input, line))
+            .collect::<Result<_, _>>()?;
         Ok(beacons.into())
     }
 }
 
 impl FromStr for BeaconMap {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let scanners = input.split("\n\n").map(|s| s.parse().unwrap()).collect();
+        let scanners = input
+            .split("\n\n")
+            .map(|s| parse_part(input, s))
+            .collect::<Result<_, _>>()?;
         Ok(Self {
             unmapped_scanners: scanners,
             scanner_positions: Vec::new(),
@@ -157,6 +164,28 @@ pub fn part_2(mut map: BeaconMap) -> i32 {
         .unwrap()
 }
 
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = BeaconMap;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
@@ -2,6 +2,8 @@ use std::fmt;
 use std::ops::{Add, Neg, Sub};
 use std::str::FromStr;
 
+use crate::{parse_part, Error};
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub struct Vector {
     x: i32,
@@ -94,14 +96,17 @@ impl Vector {
 }
 
 impl FromStr for Vector {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut numbers = input.splitn(3, ',').map(|s| s.parse::<i32>().unwrap());
-        let x = numbers.next().unwrap();
-        let y = numbers.next().unwrap();
-        let z = numbers.next().unwrap();
-        assert_eq!(numbers.next(), None);
+        let mut numbers = input.splitn(3, ',');
+        let mut next = || match numbers.next() {
+            Some(number) => parse_part(input, number),
+            None => Err(Error::at_end(input, "expected 3 coordinates")),
+        };
+        let x = next()?;
+        let y = next()?;
+        let z = next()?;
         Ok(Self { x, y, z })
     }
 }
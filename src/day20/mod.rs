@@ -1,3 +1,4 @@
+use crate::{parse_part, split_once, try_parse_str, Error, Grid, Solution};
 use std::fmt;
 use std::fmt::Formatter;
 use std::str::FromStr;
@@ -10,19 +11,14 @@ pub struct Input {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Image {
-    pixels: Vec<Vec<bool>>,
+    pixels: Grid<bool>,
     negative: bool,
 }
 
 impl fmt::Display for Image {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        for row in &self.pixels {
-            for pixel in row {
-                write!(f, "{}", if *pixel { '#' } else { '.' })?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        let pixels = self.pixels.map(|&pixel| if pixel { '#' } else { '.' });
+        write!(f, "{}", pixels)
     }
 }
 
@@ -30,15 +26,11 @@ impl Image {
     #[allow(clippy::cast_possible_wrap)]
     fn enhance(&self, algorithm: &[bool]) -> Self {
         let negative = algorithm[0] ^ self.negative;
-        let x_max = self.pixels[0].len() as isize;
-        let y_max = self.pixels.len() as isize;
-        let pixels = (-1..=y_max)
-            .map(|y| {
-                (-1..=x_max)
-                    .map(|x| self.get_enhanced_pixel(x, y, algorithm))
-                    .collect()
-            })
-            .collect();
+        let width = self.pixels.width() + 2;
+        let height = self.pixels.height() + 2;
+        let pixels = Grid::from_fn(width, height, |(x, y)| {
+            self.get_enhanced_pixel(x as isize - 1, y as isize - 1, algorithm)
+        });
         Self { pixels, negative }
     }
 
@@ -66,40 +58,57 @@ impl Image {
             self.negative
         } else {
             self.pixels
-                .get(ty as usize)
-                .and_then(|row| row.get(tx as usize))
+                .get((tx as usize, ty as usize))
                 .copied()
                 .unwrap_or(self.negative)
         }
     }
 
     fn count(&self) -> usize {
-        self.pixels.iter().flatten().filter(|&&x| x).count()
+        self.pixels.values().filter(|&&x| x).count()
+    }
+}
+
+const fn parse_pixel(c: char) -> Option<bool> {
+    match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
     }
 }
 
 impl FromStr for Image {
-    type Err = ();
+    type Err = Error;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let pixels = input
-            .lines()
-            .map(|line| line.chars().map(|c| c == '#').collect())
-            .collect();
         Ok(Self {
-            pixels,
+            pixels: Grid::parse_chars(input, "pixel", parse_pixel)?,
             negative: false,
         })
     }
 }
 
 impl FromStr for Input {
-    type Err = ();
+    type Err = Error;
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let (algorithm, image) = input.split_once("\n\n").unwrap();
-        let algorithm = algorithm.chars().map(|c| c == '#').collect();
+        let (algorithm, image) = split_once(input, "\n\n")?;
+        let algorithm = algorithm
+            .char_indices()
+            .map(|(index, c)| {
+                parse_pixel(c).ok_or_else(|| {
+                    Error::at(input, &algorithm[index..], format!("invalid pixel '{}'", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if algorithm.len() != 512 {
+            return Err(Error::at(
+                input,
+                input,
+                "image enhancement algorithm must have 512 entries",
+            ));
+        }
         Ok(Self {
             algorithm,
-            image: image.parse().unwrap(),
+            image: parse_part(input, image)?,
         })
     }
 }
@@ -120,6 +129,28 @@ fn enhance(input: Input, times: usize) -> Image {
     image
 }
 
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
@@ -1,3 +1,4 @@
+use crate::{parse_part, split_once, try_parse_str, Error, Solution};
 use std::cmp::{max, min};
 use std::str::FromStr;
 
@@ -126,24 +127,38 @@ impl DeterministicDice {
 }
 
 impl FromStr for Game {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut lines = input.lines();
+        let mut next_player = || match lines.next() {
+            Some(line) => parse_part(input, line),
+            None => Err(Error::at_end(input, "expected 2 players")),
+        };
         Ok(Self {
-            player_1: lines.next().unwrap().parse().unwrap(),
-            player_2: lines.next().unwrap().parse().unwrap(),
+            player_1: next_player()?,
+            player_2: next_player()?,
             turn: true,
         })
     }
 }
 
 impl FromStr for Player {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let score = 0;
-        let position = input[28..].parse().unwrap();
+        let (_, position) = split_once(input, "starting position: ")?;
+        let position = match parse_part(input, position)? {
+            position @ 1..=10 => position,
+            _ => {
+                return Err(Error::at(
+                    input,
+                    position,
+                    "position must be between 1 and 10",
+                ))
+            }
+        };
         Ok(Self { score, position })
     }
 }
@@ -180,6 +195,28 @@ pub fn part_2(game: Game) -> u64 {
     max(p1_wins, p2_wins)
 }
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = Game;
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(mut input: Self::Input) -> Self::Part1 {
+        part_1(&mut input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
@@ -1,6 +1,12 @@
+use crate::{parse_range, split_once, try_parse_str_lines, Error, Solution};
+pub use octree::Octree;
+pub use shape::{merge, obj, ply, Obj, Ply};
 use std::cmp;
 use std::str::FromStr;
 
+mod octree;
+mod shape;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub struct Vector {
     pub x: i32,
@@ -16,7 +22,26 @@ pub struct Cuboid {
 }
 
 impl Cuboid {
-    fn compensate(&self, other: &Self) -> Option<Self> {
+    /// `min` and `max` are both included
+    pub const fn new(min: Vector, max: Vector, on: bool) -> Self {
+        Self { min, max, on }
+    }
+
+    pub const fn min(&self) -> Vector {
+        self.min
+    }
+
+    pub const fn max(&self) -> Vector {
+        self.max
+    }
+
+    /// Whether the reboot step turns its cubes on
+    pub const fn on(&self) -> bool {
+        self.on
+    }
+
+    /// The cubes in both, in the state of `self`
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
         let min = Vector {
             x: cmp::max(self.min.x, other.min.x),
             y: cmp::max(self.min.y, other.min.y),
@@ -30,29 +55,164 @@ impl Cuboid {
         if min.x > max.x || min.y > max.y || min.z > max.z {
             None
         } else {
-            Some(Self {
-                min,
-                max,
-                on: !self.on,
-            })
+            Some(Self { min, max, ..*self })
         }
     }
 
+    fn compensate(&self, other: &Self) -> Option<Self> {
+        self.intersection(other).map(|cuboid| Self {
+            on: !self.on,
+            ..cuboid
+        })
+    }
+
+    /// Whether every cube of `other` is in `self`
+    pub const fn contains(&self, other: &Self) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && self.min.z <= other.min.z
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+            && other.max.z <= self.max.z
+    }
+
+    pub const fn contains_cube(&self, cube: Vector) -> bool {
+        self.contains(&Self::new(cube, cube, self.on))
+    }
+
     #[allow(clippy::cast_sign_loss)]
-    const fn volume(&self) -> u64 {
+    pub const fn volume(&self) -> u64 {
         let x = (self.max.x - self.min.x + 1) as u64;
         let y = (self.max.y - self.min.y + 1) as u64;
         let z = (self.max.z - self.min.z + 1) as u64;
         x * y * z
     }
+
+    /// Smallest cuboid holding both, in the state of `self`
+    fn hull(&self, other: &Self) -> Self {
+        Self {
+            min: Vector {
+                x: cmp::min(self.min.x, other.min.x),
+                y: cmp::min(self.min.y, other.min.y),
+                z: cmp::min(self.min.z, other.min.z),
+            },
+            max: Vector {
+                x: cmp::max(self.max.x, other.max.x),
+                y: cmp::max(self.max.y, other.max.y),
+                z: cmp::max(self.max.z, other.max.z),
+            },
+            on: self.on,
+        }
+    }
+
+    /// Halves on either side of the first bound of `cut` inside `self`, `None` if it has none
+    fn split(&self, cut: &Self) -> Option<(Self, Self)> {
+        let (mut low, mut high) = (*self, *self);
+        let axes = [
+            (self.min.x, self.max.x, cut.min.x, cut.max.x),
+            (self.min.y, self.max.y, cut.min.y, cut.max.y),
+            (self.min.z, self.max.z, cut.min.z, cut.max.z),
+        ];
+        let (axis, at) =
+            axes.iter()
+                .enumerate()
+                .find_map(|(axis, &(min, max, cut_min, cut_max))| {
+                    if min < cut_min && cut_min <= max {
+                        Some((axis, cut_min))
+                    } else if min <= cut_max && cut_max < max {
+                        Some((axis, cut_max + 1))
+                    } else {
+                        None
+                    }
+                })?;
+        match axis {
+            0 => (low.max.x, high.min.x) = (at - 1, at),
+            1 => (low.max.y, high.min.y) = (at - 1, at),
+            _ => (low.max.z, high.min.z) = (at - 1, at),
+        }
+        Some((low, high))
+    }
+}
+
+/// The state of the reactor after reboot steps, and what can be asked about it.
+pub trait Reactor: Default {
+    /// Applies a reboot step
+    fn add(&mut self, cuboid: Cuboid);
+
+    /// Number of cubes that are on
+    fn count(&self) -> u64;
+
+    fn is_on(&self, cube: Vector) -> bool;
+
+    /// Number of cubes that are on within `region`, whose own state is ignored
+    fn volume_in(&self, region: &Cuboid) -> u64;
+
+    /// Cuboids that are on, do not overlap, and cover exactly the cubes that are on
+    fn lit_cuboids(&self) -> Vec<Cuboid>;
+
+    /// [`Reactor::lit_cuboids`] with neighbours sharing a whole face merged, see [`merge`]
+    fn disjoint_cuboids(&self) -> Vec<Cuboid> {
+        merge(self.lit_cuboids())
+    }
 }
 
+/// Applies all `cuboids` in order to a fresh reactor
+pub fn reboot<R: Reactor>(cuboids: impl Iterator<Item = Cuboid>) -> R {
+    let mut reactor = R::default();
+    for cuboid in cuboids {
+        reactor.add(cuboid);
+    }
+    reactor
+}
+
+/// Inclusion-exclusion: every step adds cuboids cancelling its overlap with the previous ones.
+///
+/// Steps overlapping many earlier ones make the list grow quadratically, see [`Octree`].
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
-struct ReactorCore {
+pub struct ReactorCore {
     cubes: Vec<Cuboid>,
 }
 
 impl ReactorCore {
+    /// Sum of the volumes of `cubes`, negative for those that are off.
+    ///
+    /// Partial sums can be negative when the cubes are clipped to a region, only the total can't.
+    fn signed_volume(cubes: impl Iterator<Item = Cuboid>) -> u64 {
+        let total = cubes.fold(0, |acc, cuboid| {
+            let volume = i128::from(cuboid.volume());
+            if cuboid.on {
+                acc + volume
+            } else {
+                acc - volume
+            }
+        });
+        u64::try_from(total).expect("Negative volume")
+    }
+
+    /// Splits `region` until every cube overlapping a part covers all of it
+    fn decompose(region: Cuboid, cubes: &[&Cuboid], lit: &mut Vec<Cuboid>) {
+        let cubes: Vec<&Cuboid> = cubes
+            .iter()
+            .copied()
+            .filter(|cuboid| cuboid.intersection(&region).is_some())
+            .collect();
+        match cubes.iter().find_map(|cuboid| region.split(cuboid)) {
+            Some((low, high)) => {
+                Self::decompose(low, &cubes, lit);
+                Self::decompose(high, &cubes, lit);
+            }
+            None => {
+                // counter cubes cancel all but at most one of the cubes that are on
+                let on = cubes.iter().filter(|cuboid| cuboid.on).count();
+                if 2 * on > cubes.len() {
+                    lit.push(region);
+                }
+            }
+        }
+    }
+}
+
+impl Reactor for ReactorCore {
     fn add(&mut self, cuboid: Cuboid) {
         let mut counter_cubes = self
             .cubes
@@ -66,39 +226,60 @@ impl ReactorCore {
     }
 
     fn count(&self) -> u64 {
-        self.cubes.iter().fold(0, |acc, cuboid| {
-            if cuboid.on {
-                acc + cuboid.volume()
-            } else {
-                acc - cuboid.volume()
-            }
-        })
+        Self::signed_volume(self.cubes.iter().copied())
+    }
+
+    fn is_on(&self, cube: Vector) -> bool {
+        let containing = self.cubes.iter().filter(|c| c.contains_cube(cube));
+        containing.map(|c| if c.on { 1 } else { -1 }).sum::<i32>() > 0
+    }
+
+    fn volume_in(&self, region: &Cuboid) -> u64 {
+        Self::signed_volume(self.cubes.iter().filter_map(|c| c.intersection(region)))
+    }
+
+    fn lit_cuboids(&self) -> Vec<Cuboid> {
+        let mut lit = Vec::new();
+        let on = self.cubes.iter().filter(|cuboid| cuboid.on);
+        if let Some(bounds) = on.copied().reduce(|a, b| a.hull(&b)) {
+            let cubes: Vec<&Cuboid> = self.cubes.iter().collect();
+            Self::decompose(bounds, &cubes, &mut lit);
+        }
+        lit
     }
 }
 
 impl FromStr for Cuboid {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let on = input.starts_with("on");
-        let coords: Vec<i32> = input
-            .split_once(' ')
-            .unwrap()
-            .1
-            .split(',')
-            .flat_map(|s| s[2..].split(".."))
-            .map(|s| s.parse::<i32>().unwrap())
-            .collect();
+        let (state, ranges) = split_once(input, " ")?;
+        let on = match state {
+            "on" => true,
+            "off" => false,
+            _ => return Err(Error::at(input, state, "expected 'on' or 'off'")),
+        };
+        let mut ranges = ranges.split(',');
+        let mut next_range = |prefix: &str| match ranges.next() {
+            Some(range) => parse_range(input, range, prefix),
+            None => Err(Error::at_end(input, format!("expected '{}'", prefix))),
+        };
+        let (x_min, x_max) = next_range("x=")?;
+        let (y_min, y_max) = next_range("y=")?;
+        let (z_min, z_max) = next_range("z=")?;
+        if let Some(extra) = ranges.next() {
+            return Err(Error::at(input, extra, "expected only x, y and z ranges"));
+        }
         Ok(Self {
             min: Vector {
-                x: coords[0],
-                y: coords[2],
-                z: coords[4],
+                x: x_min,
+                y: y_min,
+                z: z_min,
             },
             max: Vector {
-                x: coords[1],
-                y: coords[3],
-                z: coords[5],
+                x: x_max,
+                y: y_max,
+                z: z_max,
             },
             on,
         })
@@ -106,23 +287,36 @@ impl FromStr for Cuboid {
 }
 
 pub fn part_1(cuboids: impl Iterator<Item = Cuboid>) -> u64 {
-    let mut core = ReactorCore::default();
-    for cuboid in cuboids {
-        let Cuboid { min, max, .. } = &cuboid;
-        if min.x >= -50 && max.x <= 50 && min.y >= -50 && max.y <= 50 && min.z >= -50 && max.z <= 50
-        {
-            core.add(cuboid);
-        }
-    }
-    core.count()
+    let initialization = cuboids.filter(|Cuboid { min, max, .. }| {
+        min.x >= -50 && max.x <= 50 && min.y >= -50 && max.y <= 50 && min.z >= -50 && max.z <= 50
+    });
+    reboot::<ReactorCore>(initialization).count()
 }
 
 pub fn part_2(cuboids: impl Iterator<Item = Cuboid>) -> u64 {
-    let mut core = ReactorCore::default();
-    for cuboid in cuboids {
-        core.add(cuboid);
+    reboot::<ReactorCore>(cuboids).count()
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = Vec<Cuboid>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str_lines(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input.into_iter())
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input.into_iter())
     }
-    core.count()
 }
 
 #[cfg(test)]
@@ -155,6 +349,30 @@ mod tests {
         assert_eq!(1_199_121_349_148_621, part_2(&mut cuboids));
     }
 
+    #[test]
+    fn invalid_reboot_step_is_an_error() {
+        let error =
+            try_parse_str_lines::<Cuboid>("on x=1..2,y=1..2,z=1..2\nflip x=1..2,y=1..2,z=1..2")
+                .unwrap_err();
+        assert_eq!(
+            "line 2, column 1: expected 'on' or 'off'",
+            error.to_string()
+        );
+        let error = "on x=1..2,y=1..2,w=1..2".parse::<Cuboid>().unwrap_err();
+        assert_eq!((Some(1), Some(18)), (error.line, error.column));
+    }
+
+    #[test]
+    fn volume_in_region_clipping_an_earlier_step() {
+        let core: ReactorCore = reboot(parse_str_lines(
+            "on x=0..2,y=0..0,z=0..0\n\
+             off x=1..1,y=0..0,z=0..0\n\
+             on x=1..1,y=0..0,z=0..0",
+        ));
+        let region = "on x=1..1,y=0..0,z=0..0".parse().unwrap();
+        assert_eq!(1, core.volume_in(&region));
+    }
+
     const EXAMPLE_1: &str = "\
         on x=-20..26,y=-36..17,z=-47..7\n\
         on x=-20..33,y=-21..23,z=-26..28\n\
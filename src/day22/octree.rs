@@ -0,0 +1,342 @@
+use std::array;
+
+use super::{Cuboid, Reactor, Vector};
+
+/// Half-open box, `min` included and `max` excluded on every axis
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+struct Bounds {
+    min: [i64; 3],
+    max: [i64; 3],
+}
+
+impl Bounds {
+    /// Every cube a [`Cuboid`] can hold
+    const ALL: Self = Self {
+        min: [i32::MIN as i64; 3],
+        max: [i32::MAX as i64 + 1; 3],
+    };
+
+    fn of(cuboid: &Cuboid) -> Self {
+        let Cuboid { min, max, .. } = cuboid;
+        Self {
+            min: [min.x.into(), min.y.into(), min.z.into()],
+            max: [
+                i64::from(max.x) + 1,
+                i64::from(max.y) + 1,
+                i64::from(max.z) + 1,
+            ],
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    const fn to_cuboid(self) -> Cuboid {
+        let [x, y, z] = self.min;
+        let min = Vector {
+            x: x as i32,
+            y: y as i32,
+            z: z as i32,
+        };
+        let [x, y, z] = self.max;
+        let max = Vector {
+            x: (x - 1) as i32,
+            y: (y - 1) as i32,
+            z: (z - 1) as i32,
+        };
+        Cuboid::new(min, max, true)
+    }
+
+    fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = array::from_fn(|axis| self.min[axis].max(other.min[axis]));
+        let max = array::from_fn(|axis| self.max[axis].min(other.max[axis]));
+        (0..3)
+            .all(|axis| min[axis] < max[axis])
+            .then_some(Self { min, max })
+    }
+
+    fn is_empty(&self) -> bool {
+        (0..3).any(|axis| self.min[axis] >= self.max[axis])
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    fn volume(&self) -> u64 {
+        (0..3)
+            .map(|axis| (self.max[axis] - self.min[axis]) as u64)
+            .product()
+    }
+
+    /// Child `index` when split at `split`, bit `axis` of `index` set for the upper side
+    fn octant(&self, split: [i64; 3], index: usize) -> Self {
+        let mut octant = *self;
+        for (axis, &at) in split.iter().enumerate() {
+            if index >> axis & 1 == 0 {
+                octant.max[axis] = at;
+            } else {
+                octant.min[axis] = at;
+            }
+        }
+        octant
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Leaf(bool),
+    /// Children in [`Bounds::octant`] order, some may be empty when an axis is not split
+    Branch {
+        split: [i64; 3],
+        children: Box<[Node; 8]>,
+    },
+}
+
+impl Node {
+    /// Sets every cube of `target` within `bounds`, the bounds of this node, to `on`
+    fn paint(&mut self, bounds: Bounds, target: &Bounds, on: bool) {
+        let overlap = match bounds.intersection(target) {
+            Some(overlap) => overlap,
+            None => return,
+        };
+        if overlap == bounds {
+            *self = Self::Leaf(on);
+            return;
+        }
+        if let Self::Leaf(state) = *self {
+            if state == on {
+                return;
+            }
+            // split at the corner of the target inside these bounds, axes it spans are kept
+            let split = array::from_fn(|axis| {
+                if bounds.min[axis] < target.min[axis] {
+                    target.min[axis]
+                } else {
+                    target.max[axis].min(bounds.max[axis])
+                }
+            });
+            *self = Self::Branch {
+                split,
+                children: Box::new(array::from_fn(|_| Self::Leaf(state))),
+            };
+        }
+        if let Self::Branch { split, children } = self {
+            for (index, child) in children.iter_mut().enumerate() {
+                child.paint(bounds.octant(*split, index), target, on);
+            }
+            if let Self::Leaf(first) = children[0] {
+                if children.iter().all(|child| *child == Self::Leaf(first)) {
+                    *self = Self::Leaf(first);
+                }
+            }
+        }
+    }
+
+    fn is_on(&self, cube: [i64; 3]) -> bool {
+        match self {
+            Self::Leaf(on) => *on,
+            Self::Branch { split, children } => {
+                let index = (0..3)
+                    .filter(|&axis| cube[axis] >= split[axis])
+                    .map(|axis| 1 << axis)
+                    .sum::<usize>();
+                children[index].is_on(cube)
+            }
+        }
+    }
+
+    fn volume_in(&self, bounds: Bounds, region: &Bounds) -> u64 {
+        let overlap = match bounds.intersection(region) {
+            Some(overlap) => overlap,
+            None => return 0,
+        };
+        match self {
+            Self::Leaf(true) => overlap.volume(),
+            Self::Leaf(false) => 0,
+            Self::Branch { split, children } => (0..8)
+                .map(|index| children[index].volume_in(bounds.octant(*split, index), region))
+                .sum(),
+        }
+    }
+
+    fn lit(&self, bounds: Bounds, lit: &mut Vec<Cuboid>) {
+        match self {
+            Self::Leaf(true) => lit.push(bounds.to_cuboid()),
+            Self::Leaf(false) => {}
+            Self::Branch { split, children } => {
+                for (index, child) in children.iter().enumerate() {
+                    let octant = bounds.octant(*split, index);
+                    if !octant.is_empty() {
+                        child.lit(octant, lit);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sparse octree painting each step over the previous ones.
+///
+/// Nodes split at a corner of the step that lies inside them rather than at their centre, and
+/// merge back once all their children agree, so its size follows the shape that is lit rather
+/// than the number of overlaps between steps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Octree {
+    root: Node,
+}
+
+impl Default for Octree {
+    fn default() -> Self {
+        Self {
+            root: Node::Leaf(false),
+        }
+    }
+}
+
+impl Reactor for Octree {
+    fn add(&mut self, cuboid: Cuboid) {
+        self.root
+            .paint(Bounds::ALL, &Bounds::of(&cuboid), cuboid.on);
+    }
+
+    fn count(&self) -> u64 {
+        self.root.volume_in(Bounds::ALL, &Bounds::ALL)
+    }
+
+    fn is_on(&self, cube: Vector) -> bool {
+        self.root
+            .is_on([cube.x.into(), cube.y.into(), cube.z.into()])
+    }
+
+    fn volume_in(&self, region: &Cuboid) -> u64 {
+        self.root.volume_in(Bounds::ALL, &Bounds::of(region))
+    }
+
+    fn lit_cuboids(&self) -> Vec<Cuboid> {
+        let mut lit = Vec::new();
+        self.root.lit(Bounds::ALL, &mut lit);
+        lit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file_lines;
+
+    use super::super::{reboot, ReactorCore};
+    use super::*;
+
+    fn cuboid(min: (i32, i32, i32), max: (i32, i32, i32), on: bool) -> Cuboid {
+        let vector = |(x, y, z)| Vector { x, y, z };
+        Cuboid::new(vector(min), vector(max), on)
+    }
+
+    /// The first steps of the puzzle input, enough to overlap but quick to decompose
+    fn steps() -> Vec<Cuboid> {
+        parse_file_lines("src/day22/input.txt").take(40).collect()
+    }
+
+    #[test]
+    fn answers_the_same_queries_as_the_core() {
+        let core: ReactorCore = reboot(steps().into_iter());
+        let octree: Octree = reboot(steps().into_iter());
+        assert_eq!(core.count(), octree.count());
+        let region = cuboid((-20_000, -30_000, -40_000), (40_000, 30_000, 20_000), true);
+        assert_eq!(core.volume_in(&region), octree.volume_in(&region));
+        for step in steps() {
+            for cube in [step.min, step.max] {
+                assert_eq!(core.is_on(cube), octree.is_on(cube), "{:?}", cube);
+            }
+        }
+    }
+
+    /// Linear congruential generator, to get the same "random" reboots every run
+    struct Generator(u64);
+
+    impl Generator {
+        fn next(&mut self, bound: i32) -> i32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (self.0 >> 33) as i32 % bound
+        }
+
+        fn cuboid(&mut self, on: bool) -> Cuboid {
+            let mut range = || {
+                let min = self.next(12) - 6;
+                (min, min + self.next(6))
+            };
+            let ((x1, x2), (y1, y2), (z1, z2)) = (range(), range(), range());
+            cuboid((x1, y1, z1), (x2, y2, z2), on)
+        }
+    }
+
+    #[test]
+    fn random_region_volumes_match_the_core() {
+        let mut generator = Generator(22);
+        for _ in 0..20 {
+            let steps: Vec<Cuboid> = (0..30)
+                .map(|_| {
+                    let on = generator.next(3) != 0;
+                    generator.cuboid(on)
+                })
+                .collect();
+            let core: ReactorCore = reboot(steps.iter().copied());
+            let octree: Octree = reboot(steps.into_iter());
+            for _ in 0..20 {
+                let region = generator.cuboid(true);
+                assert_eq!(core.volume_in(&region), octree.volume_in(&region));
+            }
+        }
+    }
+
+    #[test]
+    fn lit_cuboids_are_disjoint_and_cover_the_count() {
+        let core: ReactorCore = reboot(steps().into_iter());
+        let octree: Octree = reboot(steps().into_iter());
+        for lit in [core.lit_cuboids(), octree.lit_cuboids()] {
+            let volume: u64 = lit.iter().map(Cuboid::volume).sum();
+            assert_eq!(core.count(), volume);
+            for (index, a) in lit.iter().enumerate() {
+                assert!(a.on());
+                assert!(lit[index + 1..].iter().all(|b| a.intersection(b).is_none()));
+            }
+        }
+    }
+
+    #[test]
+    fn small_reboot() {
+        let mut octree = Octree::default();
+        octree.add(cuboid((10, 10, 10), (12, 12, 12), true));
+        octree.add(cuboid((11, 11, 11), (13, 13, 13), true));
+        octree.add(cuboid((9, 9, 9), (11, 11, 11), false));
+        octree.add(cuboid((10, 10, 10), (10, 10, 10), true));
+        assert_eq!(39, octree.count());
+        assert!(octree.is_on(Vector {
+            x: 10,
+            y: 10,
+            z: 10
+        }));
+        assert!(!octree.is_on(Vector {
+            x: 11,
+            y: 11,
+            z: 11
+        }));
+        assert!(octree.is_on(Vector {
+            x: 13,
+            y: 13,
+            z: 13
+        }));
+        let region = cuboid((12, 12, 12), (20, 20, 20), false);
+        assert_eq!(8, octree.volume_in(&region));
+        octree.add(cuboid(
+            (i32::MIN, i32::MIN, i32::MIN),
+            (i32::MAX, i32::MAX, i32::MAX),
+            false,
+        ));
+        assert_eq!(Octree::default(), octree);
+    }
+
+    #[test]
+    fn part_2_works() {
+        let octree: Octree = reboot(parse_file_lines("src/day22/input.txt"));
+        assert_eq!(1_199_121_349_148_621, octree.count());
+    }
+}
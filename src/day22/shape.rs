@@ -0,0 +1,232 @@
+use std::fmt;
+
+use super::{Cuboid, Vector};
+
+/// Corners of a cuboid as bits, 1 for x, 2 for y and 4 for z on the upper side
+const FACES: [[usize; 4]; 6] = [
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+];
+
+/// Inclusive bounds of a cuboid that is on, indexed by axis
+type Corners = ([i32; 3], [i32; 3]);
+
+fn to_arrays(cuboid: &Cuboid) -> Corners {
+    let Cuboid { min, max, .. } = cuboid;
+    ([min.x, min.y, min.z], [max.x, max.y, max.z])
+}
+
+fn from_arrays(min: [i32; 3], max: [i32; 3]) -> Cuboid {
+    let vector = |[x, y, z]: [i32; 3]| Vector { x, y, z };
+    Cuboid::new(vector(min), vector(max), true)
+}
+
+/// Joins disjoint cuboids that share a whole face, until no two do.
+///
+/// The result is not always the fewest cuboids possible, but merging along every axis in turn
+/// gets close for the shapes reboot steps leave.
+pub fn merge(cuboids: Vec<Cuboid>) -> Vec<Cuboid> {
+    let mut cuboids: Vec<_> = cuboids.iter().map(to_arrays).collect();
+    let mut len = usize::MAX;
+    while cuboids.len() < len {
+        len = cuboids.len();
+        for axis in 0..3 {
+            cuboids = merge_along(cuboids, axis);
+        }
+    }
+    cuboids
+        .into_iter()
+        .map(|(min, max)| from_arrays(min, max))
+        .collect()
+}
+
+fn merge_along(mut cuboids: Vec<Corners>, axis: usize) -> Vec<Corners> {
+    // the extent on the other axes, then the position along `axis`
+    let key = |(min, max): &Corners| {
+        let (mut min, mut max) = (*min, *max);
+        let position = min[axis];
+        min[axis] = 0;
+        max[axis] = 0;
+        (min, max, position)
+    };
+    cuboids.sort_unstable_by_key(key);
+    let mut merged: Vec<Corners> = Vec::with_capacity(cuboids.len());
+    for (min, max) in cuboids {
+        if let Some((last_min, last_max)) = merged.last_mut() {
+            let same_face = (0..3)
+                .filter(|&other| other != axis)
+                .all(|other| last_min[other] == min[other] && last_max[other] == max[other]);
+            if same_face && last_max[axis] + 1 == min[axis] {
+                last_max[axis] = max[axis];
+                continue;
+            }
+        }
+        merged.push((min, max));
+    }
+    merged
+}
+
+/// Corners of the unit cubes at both ends of `cuboid`, indexed like [`FACES`]
+fn corners(cuboid: &Cuboid) -> [[i64; 3]; 8] {
+    let (min, max) = to_arrays(cuboid);
+    let mut corners = [[0; 3]; 8];
+    for (index, corner) in corners.iter_mut().enumerate() {
+        for axis in 0..3 {
+            corner[axis] = if index >> axis & 1 == 0 {
+                i64::from(min[axis])
+            } else {
+                i64::from(max[axis]) + 1
+            };
+        }
+    }
+    corners
+}
+
+/// Wavefront OBJ mesh with a closed box of 8 vertices and 6 quads per cuboid, from [`obj`].
+pub struct Obj<'a>(&'a [Cuboid]);
+
+/// Cubes span a unit on each axis, so a cuboid from 0 to 1 is 2 units wide
+pub fn obj(cuboids: &[Cuboid]) -> Obj<'_> {
+    Obj(cuboids)
+}
+
+impl fmt::Display for Obj<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cuboid in self.0 {
+            for [x, y, z] in corners(cuboid) {
+                writeln!(f, "v {} {} {}", x, y, z)?;
+            }
+        }
+        for index in 0..self.0.len() {
+            for face in FACES {
+                // vertices count from 1
+                let [a, b, c, d] = face.map(|corner| 8 * index + corner + 1);
+                writeln!(f, "f {} {} {} {}", a, b, c, d)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ASCII PLY mesh with the same vertices and faces as [`Obj`], from [`ply`].
+pub struct Ply<'a>(&'a [Cuboid]);
+
+/// See [`obj`]
+pub fn ply(cuboids: &[Cuboid]) -> Ply<'_> {
+    Ply(cuboids)
+}
+
+impl fmt::Display for Ply<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ply")?;
+        writeln!(f, "format ascii 1.0")?;
+        writeln!(f, "element vertex {}", 8 * self.0.len())?;
+        for axis in ["x", "y", "z"] {
+            writeln!(f, "property int {}", axis)?;
+        }
+        writeln!(f, "element face {}", 6 * self.0.len())?;
+        writeln!(f, "property list uchar int vertex_indices")?;
+        writeln!(f, "end_header")?;
+        for cuboid in self.0 {
+            for [x, y, z] in corners(cuboid) {
+                writeln!(f, "{} {} {}", x, y, z)?;
+            }
+        }
+        for index in 0..self.0.len() {
+            for face in FACES {
+                let [a, b, c, d] = face.map(|corner| 8 * index + corner);
+                writeln!(f, "4 {} {} {} {}", a, b, c, d)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_str_lines;
+
+    use super::super::{reboot, Octree, Reactor, ReactorCore};
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        on x=10..12,y=10..12,z=10..12\n\
+        on x=11..13,y=11..13,z=11..13\n\
+        off x=9..11,y=9..11,z=9..11\n\
+        on x=10..10,y=10..10,z=10..10\n\
+    ";
+
+    fn cuboid(min: [i32; 3], max: [i32; 3]) -> Cuboid {
+        from_arrays(min, max)
+    }
+
+    #[test]
+    fn merges_cuboids_sharing_a_face() {
+        let cuboids = vec![
+            cuboid([0, 0, 0], [1, 1, 1]),
+            cuboid([2, 0, 0], [3, 1, 1]),
+            cuboid([0, 2, 0], [3, 2, 1]),
+            cuboid([5, 0, 0], [5, 1, 1]),
+        ];
+        let merged = merge(cuboids);
+        assert_eq!(
+            vec![cuboid([0, 0, 0], [3, 2, 1]), cuboid([5, 0, 0], [5, 1, 1])],
+            merged
+        );
+    }
+
+    #[test]
+    fn covers_exactly_the_cubes_that_are_on() {
+        let core: ReactorCore = reboot(parse_str_lines(EXAMPLE));
+        let octree: Octree = reboot(parse_str_lines(EXAMPLE));
+        let shapes = [
+            (core.lit_cuboids(), core.disjoint_cuboids()),
+            (octree.lit_cuboids(), octree.disjoint_cuboids()),
+        ];
+        for (lit, shape) in shapes {
+            assert!(shape.len() < lit.len());
+            assert_eq!(39, shape.iter().map(Cuboid::volume).sum::<u64>());
+            for x in 8..15 {
+                for y in 8..15 {
+                    for z in 8..15 {
+                        let cube = Vector { x, y, z };
+                        let covering = shape.iter().filter(|c| c.contains_cube(cube)).count();
+                        assert_eq!(usize::from(core.is_on(cube)), covering, "{:?}", cube);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exports_a_unit_cube() {
+        let cube = [cuboid([0, 0, 0], [0, 0, 0])];
+        let obj = obj(&cube).to_string();
+        assert!(obj.starts_with("v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nv 0 0 1\n"));
+        let faces = "\
+            f 1 5 7 3\n\
+            f 2 4 8 6\n\
+            f 1 2 6 5\n\
+            f 3 7 8 4\n\
+            f 1 3 4 2\n\
+            f 5 6 8 7\n";
+        assert!(obj.ends_with(faces));
+        let ply = ply(&cube).to_string();
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 8\n"));
+        assert!(ply.contains("element face 6\n"));
+        let body = "\
+            end_header\n\
+            0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n\
+            4 0 4 6 2\n\
+            4 1 3 7 5\n\
+            4 0 1 5 4\n\
+            4 2 6 7 3\n\
+            4 0 2 3 1\n\
+            4 4 5 7 6\n";
+        assert!(ply.ends_with(body));
+    }
+}
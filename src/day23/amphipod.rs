@@ -1,8 +1,8 @@
-use crate::day23::position::hall_entrance_for_room;
 use std::fmt;
 use std::fmt::Write;
 
 use super::Position;
+use crate::Error;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub struct Amphipod {
@@ -27,31 +27,6 @@ impl Path {
         self.distance() * self.amphipod.energy_cost()
     }
 
-    pub const fn energy_wasted(&self) -> usize {
-        self.distance_wasted() * 2 * self.amphipod.energy_cost()
-    }
-
-    const fn distance_wasted(&self) -> usize {
-        match (self.amphipod.position, self.target) {
-            (Position::SideRoom(room_nb, _), Position::Hallway(to)) => {
-                let from = hall_entrance_for_room(room_nb);
-                let target = hall_entrance_for_room(self.amphipod.type_.target_room_nb());
-                if from <= target && target <= to {
-                    to - target
-                } else if to <= from && from <= target {
-                    from - to
-                } else if to <= target && target <= from {
-                    target - to
-                } else if target <= from && from <= to {
-                    to - from
-                } else {
-                    0
-                }
-            }
-            _ => 0,
-        }
-    }
-
     pub const fn distance(&self) -> usize {
         self.amphipod.position.distance_to_hall()
             + self.target.distance_to_hall()
@@ -62,14 +37,20 @@ impl Path {
     }
 }
 
-const fn delta(a: usize, b: usize) -> usize {
-    if a < b {
-        b - a
-    } else {
-        a - b
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} from {} to {}",
+            self.amphipod.type_, self.amphipod.position, self.target
+        )
     }
 }
 
+const fn delta(a: usize, b: usize) -> usize {
+    a.abs_diff(b)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
 pub enum AmphipodType {
     Amber,
@@ -108,24 +89,25 @@ impl AmphipodType {
         }
     }
 
-    pub fn option_from(input: char) -> Option<Self> {
+    pub fn option_from(input: char) -> Result<Option<Self>, Error> {
         if input == '.' {
-            None
+            Ok(None)
         } else {
-            Some(Self::from(input))
+            Self::try_from(input).map(Some)
         }
     }
 }
 
-#[allow(clippy::fallible_impl_from)]
-impl From<char> for AmphipodType {
-    fn from(input: char) -> Self {
+impl TryFrom<char> for AmphipodType {
+    type Error = Error;
+
+    fn try_from(input: char) -> Result<Self, Self::Error> {
         match input {
-            'A' => Self::Amber,
-            'B' => Self::Bronze,
-            'C' => Self::Copper,
-            'D' => Self::Desert,
-            _ => panic!("Invalid Amphipod: {}", input),
+            'A' => Ok(Self::Amber),
+            'B' => Ok(Self::Bronze),
+            'C' => Ok(Self::Copper),
+            'D' => Ok(Self::Desert),
+            _ => Err(Error::new(format!("invalid amphipod '{}'", input))),
         }
     }
 }
@@ -5,6 +5,7 @@ use std::str::FromStr;
 use crate::day23::position::{hall_entrance_for_room, Position};
 
 use super::{Amphipod, AmphipodType, Path};
+use crate::Error;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
 pub struct Hallway([Option<AmphipodType>; 11]);
@@ -108,14 +109,14 @@ pub struct Burrow<const ROOM_SIZE: usize> {
 }
 
 impl<const ROOM_SIZE: usize> Burrow<ROOM_SIZE> {
-    fn side_rooms(&self) -> impl Iterator<Item = SideRoom> {
+    fn side_rooms(&self) -> impl Iterator<Item = SideRoom<'_>> {
         self.rooms
             .iter()
             .enumerate()
             .map(move |(number, room)| SideRoom { number, room })
     }
 
-    const fn side_room(&self, number: usize) -> SideRoom {
+    const fn side_room(&self, number: usize) -> SideRoom<'_> {
         let room = &self.rooms[number];
         SideRoom { number, room }
     }
@@ -193,6 +194,36 @@ impl<const ROOM_SIZE: usize> Burrow<ROOM_SIZE> {
         })
     }
 
+    /// Moving an amphipod into its room never hurts, so when one can it is the only move
+    /// considered. Otherwise every move of an amphipod out of a room into the hall.
+    pub fn moves(&self) -> Vec<Path> {
+        match self.find_path_to_room() {
+            Some(path) => vec![path],
+            None => self.paths_to_hall().collect(),
+        }
+    }
+
+    /// Burrows reachable with a single move, with the energy that move takes
+    pub fn next_moves(&self) -> Vec<(Self, usize)> {
+        self.moves()
+            .into_iter()
+            .map(|path| (self.after(path), path.energy_cost()))
+            .collect()
+    }
+
+    /// The move that turns this burrow into `next`
+    pub fn move_to(&self, next: &Self) -> Option<Path> {
+        self.moves()
+            .into_iter()
+            .find(|&path| self.after(path) == *next)
+    }
+
+    pub fn after(&self, path: Path) -> Self {
+        let mut next = *self;
+        next.apply(path);
+        next
+    }
+
     pub fn apply(&mut self, path: Path) {
         let removed = self.get_mut(path.amphipod.position).take();
         assert_eq!(removed, Some(path.amphipod.type_));
@@ -209,29 +240,33 @@ impl<const ROOM_SIZE: usize> Burrow<ROOM_SIZE> {
 }
 
 impl FromStr for Burrow<2> {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut lines = input.lines().skip(1);
-        let hall: Vec<_> = lines
-            .next()
-            .unwrap()
-            .chars()
-            .skip(1)
-            .take(11)
-            .map(AmphipodType::option_from)
-            .collect();
-        let first: Vec<char> = lines.next().unwrap().chars().collect();
-        let second: Vec<char> = lines.next().unwrap().chars().collect();
-        let room = |index: usize| {
+        let mut next_line = || {
+            lines
+                .next()
+                .ok_or_else(|| Error::at_end(input, "expected 5 lines"))
+        };
+        let spot = |line: &str, index: usize| match line.char_indices().nth(index) {
+            Some((offset, c)) => {
+                AmphipodType::option_from(c).map_err(|err| err.within(input, &line[offset..]))
+            }
+            None => Err(Error::at_end(line, "line too short").within(input, line)),
+        };
+        let hall_line = next_line()?;
+        let hall = (1..=11)
+            .map(|index| spot(hall_line, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        let first = next_line()?;
+        let second = next_line()?;
+        let room = |index: usize| -> Result<[Option<AmphipodType>; 2], Error> {
             let index = index * 2 + 3;
-            [
-                AmphipodType::option_from(first[index]),
-                AmphipodType::option_from(second[index]),
-            ]
+            Ok([spot(first, index)?, spot(second, index)?])
         };
         Ok(Self {
-            rooms: [room(0), room(1), room(2), room(3)],
+            rooms: [room(0)?, room(1)?, room(2)?, room(3)?],
             hall: Hallway([
                 hall[0], hall[1], hall[2], hall[3], hall[4], hall[5], hall[6], hall[7], hall[8],
                 hall[9], hall[10],
@@ -1,81 +1,64 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
-
-use amphipod::{Amphipod, AmphipodType, Path};
+use crate::search::astar;
+use crate::{try_parse_str, Error, Solution};
+pub use amphipod::{Amphipod, AmphipodType, Path};
 pub use burrow::Burrow;
-use position::Position;
+pub use position::Position;
+use std::fmt;
 
 mod amphipod;
 mod burrow;
 mod position;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
-struct BurrowState<const ROOM_SIZE: usize> {
-    burrow: Burrow<ROOM_SIZE>,
-    energy_spent: usize,
-    min_total_energy: usize,
+/// Cheapest sequence of moves that gets every amphipod into its room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan<const ROOM_SIZE: usize> {
+    pub start: Burrow<ROOM_SIZE>,
+    pub moves: Vec<Path>,
 }
 
-impl<const ROOM_SIZE: usize> BurrowState<ROOM_SIZE> {
-    fn new(burrow: Burrow<ROOM_SIZE>) -> Self {
-        Self {
-            burrow,
-            energy_spent: 0,
-            min_total_energy: burrow.calc_min_energy_needed(),
-        }
-    }
-
-    fn improve(&mut self) {
-        while let Some(path) = self.burrow.find_path_to_room() {
-            self.apply(path);
-        }
+impl<const ROOM_SIZE: usize> Plan<ROOM_SIZE> {
+    pub fn new(start: Burrow<ROOM_SIZE>) -> Self {
+        let found = astar(
+            start,
+            Burrow::next_moves,
+            Burrow::calc_min_energy_needed,
+            |burrow| burrow.calc_min_energy_needed() == 0,
+        )
+        .expect("No solution!");
+        let moves = found
+            .path
+            .windows(2)
+            .map(|step| step[0].move_to(&step[1]).unwrap())
+            .collect();
+        Self { start, moves }
     }
 
-    fn apply(&mut self, path: Path) {
-        self.energy_spent += path.energy_cost();
-        self.min_total_energy += path.energy_wasted();
-        self.burrow.apply(path);
+    pub fn energy(&self) -> usize {
+        self.moves.iter().map(Path::energy_cost).sum()
     }
 
-    fn new_states(&self) -> impl Iterator<Item = Self> + '_ {
-        self.burrow.paths_to_hall().map(move |path| {
-            let mut new = *self;
-            new.apply(path);
-            new
+    /// Every move together with the burrow right after it
+    pub fn replay(&self) -> impl Iterator<Item = (Path, Burrow<ROOM_SIZE>)> + '_ {
+        self.moves.iter().scan(self.start, |burrow, &path| {
+            burrow.apply(path);
+            Some((path, *burrow))
         })
     }
 }
 
-impl<const ROOM_SIZE: usize> Ord for BurrowState<ROOM_SIZE> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .min_total_energy
-            .cmp(&self.min_total_energy)
-            .then_with(|| other.energy_spent.cmp(&self.energy_spent))
-            .then_with(|| other.burrow.cmp(&self.burrow))
-    }
-}
-
-impl<const ROOM_SIZE: usize> PartialOrd for BurrowState<ROOM_SIZE> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl<const ROOM_SIZE: usize> fmt::Display for Plan<ROOM_SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        for (path, burrow) in self.replay() {
+            writeln!(f, "\n{} ({} energy)", path, path.energy_cost())?;
+            write!(f, "{}", burrow)?;
+        }
+        writeln!(f, "\ntotal energy: {}", self.energy())
     }
 }
 
 pub fn part_1<const ROOM_SIZE: usize>(burrow: Burrow<ROOM_SIZE>) -> usize {
-    let mut visited = HashSet::new();
-    let mut heap = BinaryHeap::new();
-    heap.push(BurrowState::new(burrow));
-    while let Some(mut state) = heap.pop() {
-        if visited.insert(state.burrow) {
-            state.improve();
-            if state.energy_spent == state.min_total_energy {
-                return state.energy_spent;
-            }
-            heap.extend(state.new_states());
-        }
-    }
-    panic!("No solution!")
+    Plan::new(burrow).energy()
 }
 
 pub fn part_2(burrow: Burrow<2>) -> usize {
@@ -112,6 +95,28 @@ pub fn part_2(burrow: Burrow<2>) -> usize {
     part_1(unfolded_burrow)
 }
 
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Burrow<2>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        try_parse_str(input)
+    }
+
+    fn part_1(input: Self::Input) -> Self::Part1 {
+        part_1(input)
+    }
+
+    fn part_2(input: Self::Input) -> Self::Part2 {
+        part_2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parse_file;
@@ -124,6 +129,19 @@ mod tests {
         assert_eq!(12521, part_1(burrow));
     }
 
+    #[test]
+    fn example_1_plan_replays_to_an_organized_burrow() {
+        let plan = Plan::new(EXAMPLE.parse().unwrap());
+        let energies: usize = plan.replay().map(|(path, _)| path.energy_cost()).sum();
+        assert_eq!(12521, energies);
+        let (_, last) = plan.replay().last().unwrap();
+        assert_eq!(0, last.calc_min_energy_needed());
+        let shown = plan.to_string();
+        assert!(shown.starts_with(EXAMPLE));
+        assert!(shown.ends_with("total energy: 12521\n"));
+        assert_eq!(plan.moves.len() + 1, shown.matches("#############").count());
+    }
+
     #[test]
     fn part_1_works() {
         let burrow = parse_file("src/day23/input.txt");
@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Copy)]
 pub enum Position {
     SideRoom(usize, usize),
@@ -20,6 +22,15 @@ impl Position {
     }
 }
 
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SideRoom(room_nb, spot) => write!(f, "room {} spot {}", room_nb, spot),
+            Self::Hallway(index) => write!(f, "hallway {}", index),
+        }
+    }
+}
+
 pub const fn hall_entrance_for_room(room_nb: usize) -> usize {
     (room_nb + 1) * 2
 }
@@ -0,0 +1,278 @@
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
+use super::expression::Expression;
+use super::input::{Instruction, Value, Variable};
+use super::range::Range;
+
+/// Symbolic value of a register along with the values it can take.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Register {
+    pub expression: Rc<Expression>,
+    pub range: Range,
+    /// Whether evaluating the expression can fail, which keeps it from being simplified away
+    can_fail: bool,
+}
+
+impl Register {
+    /// Expressions that can only take one value and never fail are replaced by that value
+    fn new(expression: Expression, range: Range, can_fail: bool) -> Self {
+        let expression = match range.as_constant() {
+            Some(value) if !can_fail => Expression::Value(value),
+            _ => expression,
+        };
+        Self {
+            expression: Rc::new(expression),
+            range,
+            can_fail,
+        }
+    }
+
+    /// The result of an operation on `left` and `right`, which fails when either of them does
+    fn apply(
+        left: Register,
+        right: Register,
+        operation: fn(Rc<Expression>, Rc<Expression>) -> Expression,
+        range: Range,
+        can_fail: bool,
+    ) -> Self {
+        let can_fail = can_fail || left.can_fail || right.can_fail;
+        Self::new(
+            operation(left.expression, right.expression),
+            range,
+            can_fail,
+        )
+    }
+
+    /// The value when it is known and can't fail
+    fn as_constant(&self) -> Option<i64> {
+        self.range.as_constant().filter(|_| !self.can_fail)
+    }
+
+    fn is(&self, value: i64) -> bool {
+        self.as_constant() == Some(value)
+    }
+}
+
+/// Runs instructions symbolically, simplifying with the ranges of registers and inputs.
+#[derive(Clone, Debug)]
+pub struct Alu {
+    w: Register,
+    x: Register,
+    y: Register,
+    z: Register,
+    input_index: u8,
+}
+
+impl Default for Alu {
+    /// All registers start at zero, like when running a whole program
+    fn default() -> Self {
+        let zero = Register::new(Expression::ZERO, Range::constant(0), false);
+        Self {
+            w: zero.clone(),
+            x: zero.clone(),
+            y: zero.clone(),
+            z: zero,
+            input_index: 0,
+        }
+    }
+}
+
+impl Index<Variable> for Alu {
+    type Output = Register;
+
+    fn index(&self, var: Variable) -> &Self::Output {
+        match var {
+            Variable::W => &self.w,
+            Variable::X => &self.x,
+            Variable::Y => &self.y,
+            Variable::Z => &self.z,
+        }
+    }
+}
+
+impl IndexMut<Variable> for Alu {
+    fn index_mut(&mut self, var: Variable) -> &mut Self::Output {
+        match var {
+            Variable::W => &mut self.w,
+            Variable::X => &mut self.x,
+            Variable::Y => &mut self.y,
+            Variable::Z => &mut self.z,
+        }
+    }
+}
+
+impl Alu {
+    /// Registers start with unknown values, like when running part of a program
+    pub fn unknown() -> Self {
+        let initial = |var| Register::new(Expression::Initial(var), Range::FULL, false);
+        Self {
+            w: initial(Variable::W),
+            x: initial(Variable::X),
+            y: initial(Variable::Y),
+            z: initial(Variable::Z),
+            input_index: 0,
+        }
+    }
+
+    pub fn run(mut self, instructions: &[Instruction]) -> Self {
+        for &instruction in instructions {
+            self.process(instruction);
+        }
+        self
+    }
+
+    pub fn process(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Inp(var) => self.set_input(var),
+            Instruction::Add(var, val) => self.add(var, val),
+            Instruction::Mul(var, val) => self.mul(var, val),
+            Instruction::Div(var, val) => self.div(var, val),
+            Instruction::Mod(var, val) => self.modulo(var, val),
+            Instruction::Eql(var, val) => self.eql(var, val),
+        }
+    }
+
+    pub fn registers(&self) -> [&Register; 4] {
+        Variable::ALL.map(|var| &self[var])
+    }
+
+    fn get(&self, val: Value) -> Register {
+        match val {
+            Value::Variable(var) => self[var].clone(),
+            Value::Number(number) => {
+                let number = i64::from(number);
+                Register::new(Expression::Value(number), Range::constant(number), false)
+            }
+        }
+    }
+
+    /// Current value of `var` and the value of `val`
+    fn operands(&self, var: Variable, val: Value) -> (Register, Register) {
+        let right = self.get(val);
+        (self[var].clone(), right)
+    }
+
+    fn set_input(&mut self, var: Variable) {
+        self[var] = Register::new(
+            Expression::Input {
+                index: self.input_index,
+            },
+            Range::DIGIT,
+            false,
+        );
+        self.input_index += 1;
+    }
+
+    fn add(&mut self, var: Variable, val: Value) {
+        let (left, right) = self.operands(var, val);
+        self[var] = if right.is(0) {
+            left
+        } else if left.is(0) {
+            right
+        } else {
+            let range = left.range + right.range;
+            Register::apply(left, right, Expression::Add, range, false)
+        }
+    }
+
+    fn mul(&mut self, var: Variable, val: Value) {
+        let (left, right) = self.operands(var, val);
+        self[var] = if right.is(1) {
+            left
+        } else if left.is(1) {
+            right
+        } else {
+            let range = left.range * right.range;
+            Register::apply(left, right, Expression::Mul, range, false)
+        }
+    }
+
+    /// A divisor that can be zero may fail, so the division is never simplified away and
+    /// [`Expression::evaluate`]s to `None` when it does
+    fn div(&mut self, var: Variable, val: Value) {
+        let (left, right) = self.operands(var, val);
+        self[var] = if right.is(1) {
+            left
+        } else {
+            let range = left.range / right.range;
+            let can_fail = right.range.contains(0);
+            Register::apply(left, right, Expression::Div, range, can_fail)
+        }
+    }
+
+    /// Like [`Alu::div`], a modulo that may fail is never simplified away
+    fn modulo(&mut self, var: Variable, val: Value) {
+        let (left, right) = self.operands(var, val);
+        self[var] = match right.as_constant() {
+            Some(divisor) if divisor > 0 && left.range.is_within(Range::new(0, divisor - 1)) => {
+                left
+            }
+            _ => {
+                let range = left.range % right.range;
+                let can_fail = left.range.min < 0 || right.range.min <= 0;
+                Register::apply(left, right, Expression::Mod, range, can_fail)
+            }
+        }
+    }
+
+    fn eql(&mut self, var: Variable, val: Value) {
+        let (left, right) = self.operands(var, val);
+        let range = left.range.eql(right.range);
+        self[var] = if left.expression == right.expression && !left.can_fail {
+            Register::new(Expression::ONE, Range::constant(1), false)
+        } else {
+            Register::apply(left, right, Expression::Eql, range, false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::try_parse_str_lines;
+
+    use super::*;
+
+    fn run(program: &str) -> Alu {
+        Alu::default().run(&try_parse_str_lines::<Instruction>(program).unwrap())
+    }
+
+    #[test]
+    fn input_bounds_simplify_mod_div_and_eql() {
+        let alu = run("inp w\nmod w 10\ninp x\ndiv x 10\ninp y\neql y 10");
+        assert_eq!(Expression::Input { index: 0 }, *alu.w.expression);
+        assert_eq!(Expression::ZERO, *alu.x.expression);
+        assert_eq!(Expression::ZERO, *alu.y.expression);
+    }
+
+    #[test]
+    fn ranges_follow_arithmetic() {
+        let alu = run("inp z\nmul z 26\nadd z 5\ninp x\neql x z");
+        assert_eq!(Range::new(31, 239), alu.z.range);
+        assert_eq!(Expression::ZERO, *alu.x.expression);
+    }
+
+    #[test]
+    fn failing_instructions_do_not_evaluate() {
+        // the last one divides by i64::MIN, which is -128 to the power of 9
+        let min = "add x -128\nmul x x\nmul x x\nmul x x\nadd y -128\nmul x y\ninp w\nmod w x";
+        for program in ["inp w\ndiv w 0", "inp w\nmod w 0", min] {
+            let alu = run(program);
+            assert_eq!(
+                None,
+                alu.w.expression.evaluate(&[0; 4], &[5]),
+                "{}",
+                program
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_registers_are_cleared_by_multiplying_with_zero() {
+        let alu =
+            Alu::unknown().run(&try_parse_str_lines::<Instruction>("mul x 0\nadd x z").unwrap());
+        assert!(alu.x.expression.reads(Variable::Z));
+        assert!(!alu.x.expression.reads(Variable::X));
+        assert!(!alu.y.expression.reads(Variable::X));
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use super::alu::Alu;
+use super::expression::Expression;
+use super::input::{Instruction, Variable};
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
+pub enum Operation {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
+pub enum Operand {
+    Register(u16),
+    Immediate(i64),
+}
+
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
+pub enum Op {
+    Input {
+        dst: u16,
+        index: u8,
+    },
+    Const {
+        dst: u16,
+        value: i64,
+    },
+    Apply {
+        operation: Operation,
+        dst: u16,
+        left: u16,
+        right: Operand,
+    },
+}
+
+/// Registers and inputs up to this count are kept on the stack while running
+const STACK_SIZE: usize = 16;
+
+/// A program compiled to compute a single register.
+///
+/// Compilation runs the program through the symbolic [`Alu`], so constants are folded, anything
+/// that does not contribute to the output is dropped and expressions shared between registers
+/// are computed once. The simplifications assume every input is a digit from 1 to 9.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bytecode {
+    ops: Vec<Op>,
+    registers: usize,
+    output: Operand,
+    inputs: usize,
+}
+
+impl Bytecode {
+    pub fn compile(program: &[Instruction], output: Variable) -> Self {
+        let root = Alu::default().run(program)[output].expression.clone();
+        let inputs = program
+            .iter()
+            .filter(|instruction| matches!(instruction, Instruction::Inp(_)))
+            .count();
+        let mut compiler = Compiler::default();
+        let output = compiler.operand(&root);
+        let (ops, registers, output) = compiler.allocate_registers(output);
+        Self {
+            ops,
+            registers,
+            output,
+            inputs,
+        }
+    }
+
+    /// Number of instructions, a measure of how much compilation simplified the program
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// `None` when the program would fail, see [`Expression::evaluate`]
+    pub fn run(&self, inputs: &[i64]) -> Option<i64> {
+        if self.registers <= STACK_SIZE {
+            self.run_in(&mut [0; STACK_SIZE], inputs)
+        } else {
+            self.run_in(&mut vec![0; self.registers], inputs)
+        }
+    }
+
+    fn run_in(&self, registers: &mut [i64], inputs: &[i64]) -> Option<i64> {
+        for op in &self.ops {
+            match *op {
+                Op::Input { dst, index } => {
+                    registers[usize::from(dst)] = *inputs.get(usize::from(index))?;
+                }
+                Op::Const { dst, value } => registers[usize::from(dst)] = value,
+                Op::Apply {
+                    operation,
+                    dst,
+                    left,
+                    right,
+                } => {
+                    let left = registers[usize::from(left)];
+                    let right = match right {
+                        Operand::Register(register) => registers[usize::from(register)],
+                        Operand::Immediate(value) => value,
+                    };
+                    registers[usize::from(dst)] = match operation {
+                        Operation::Add => left.checked_add(right)?,
+                        Operation::Mul => left.checked_mul(right)?,
+                        Operation::Div => left.checked_div(right)?,
+                        Operation::Mod if left < 0 || right <= 0 => return None,
+                        Operation::Mod => left % right,
+                        Operation::Eql => i64::from(left == right),
+                    };
+                }
+            }
+        }
+        Some(match self.output {
+            Operand::Register(register) => registers[usize::from(register)],
+            Operand::Immediate(value) => value,
+        })
+    }
+
+    /// Whether a program compiled for `z` accepts `model_number`, whose digits can't be 0
+    pub fn is_valid(&self, model_number: u64) -> bool {
+        let mut stack = [0; STACK_SIZE];
+        let mut heap = Vec::new();
+        let digits = if self.inputs <= STACK_SIZE {
+            &mut stack[..self.inputs]
+        } else {
+            heap.resize(self.inputs, 0);
+            &mut heap[..]
+        };
+        let mut rest = model_number;
+        for digit in digits.iter_mut().rev() {
+            *digit = i64::try_from(rest % 10).unwrap();
+            if *digit == 0 {
+                return false;
+            }
+            rest /= 10;
+        }
+        rest == 0 && self.run(digits) == Some(0)
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Add => "add",
+            Self::Mul => "mul",
+            Self::Div => "div",
+            Self::Mod => "mod",
+            Self::Eql => "eql",
+        })
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Register(register) => write!(f, "r{}", register),
+            Self::Immediate(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input { dst, index } => write!(f, "r{} = inp {}", dst, index),
+            Self::Const { dst, value } => write!(f, "r{} = {}", dst, value),
+            Self::Apply {
+                operation,
+                dst,
+                left,
+                right,
+            } => write!(f, "r{} = {} r{} {}", dst, operation, left, right),
+        }
+    }
+}
+
+/// One op per line, ending with the returned operand
+impl fmt::Display for Bytecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for op in &self.ops {
+            writeln!(f, "{}", op)?;
+        }
+        write!(f, "ret {}", self.output)
+    }
+}
+
+/// Expressions in evaluation order, each op writing to its own value number before registers
+/// are allocated.
+#[derive(Default)]
+struct Compiler {
+    ops: Vec<Op>,
+    numbers: HashMap<*const Expression, u16>,
+}
+
+impl Compiler {
+    fn operand(&mut self, expression: &Rc<Expression>) -> Operand {
+        match **expression {
+            Expression::Value(value) => Operand::Immediate(value),
+            _ => Operand::Register(self.number(expression)),
+        }
+    }
+
+    /// Value number of `expression`, emitting the ops computing it the first time it is seen
+    fn number(&mut self, expression: &Rc<Expression>) -> u16 {
+        if let Some(&number) = self.numbers.get(&Rc::as_ptr(expression)) {
+            return number;
+        }
+        let op = match &**expression {
+            Expression::Value(value) => Op::Const {
+                dst: 0,
+                value: *value,
+            },
+            Expression::Input { index } => Op::Input {
+                dst: 0,
+                index: *index,
+            },
+            Expression::Initial(_) => unreachable!("registers start at zero"),
+            Expression::Add(left, right) => self.apply(Operation::Add, left, right),
+            Expression::Mul(left, right) => self.apply(Operation::Mul, left, right),
+            Expression::Div(left, right) => self.apply(Operation::Div, left, right),
+            Expression::Mod(left, right) => self.apply(Operation::Mod, left, right),
+            Expression::Eql(left, right) => self.apply(Operation::Eql, left, right),
+        };
+        let number = u16::try_from(self.ops.len()).expect("program too large");
+        self.ops.push(op);
+        self.numbers.insert(Rc::as_ptr(expression), number);
+        number
+    }
+
+    fn apply(&mut self, operation: Operation, left: &Rc<Expression>, right: &Rc<Expression>) -> Op {
+        // constants go on the right where they become immediates
+        let commutative = matches!(operation, Operation::Add | Operation::Mul | Operation::Eql);
+        let (left, right) = match **left {
+            Expression::Value(_) if commutative => (right, left),
+            _ => (left, right),
+        };
+        Op::Apply {
+            operation,
+            dst: 0,
+            left: self.number(left),
+            right: self.operand(right),
+        }
+    }
+
+    /// Replaces value numbers by as few registers as possible, reusing a register once the
+    /// value in it is no longer needed. Returns the ops, the number of registers and `output`
+    /// in terms of registers.
+    fn allocate_registers(self, output: Operand) -> (Vec<Op>, usize, Operand) {
+        let mut last_use: Vec<usize> = (0..self.ops.len()).collect();
+        for (index, op) in self.ops.iter().enumerate() {
+            if let Op::Apply { left, right, .. } = op {
+                last_use[usize::from(*left)] = index;
+                if let Operand::Register(right) = right {
+                    last_use[usize::from(*right)] = index;
+                }
+            }
+        }
+        if let Operand::Register(number) = output {
+            last_use[usize::from(number)] = usize::MAX;
+        }
+
+        let mut registers: Vec<u16> = vec![0; self.ops.len()];
+        let mut free = Vec::new();
+        let mut count = 0;
+        let mut ops = Vec::with_capacity(self.ops.len());
+        for (index, op) in self.ops.into_iter().enumerate() {
+            let mut release = |number: u16| {
+                if last_use[usize::from(number)] == index {
+                    // read before the result is written, so the result can reuse the register
+                    last_use[usize::from(number)] = usize::MAX;
+                    free.push(registers[usize::from(number)]);
+                }
+            };
+            if let Op::Apply { left, right, .. } = op {
+                release(left);
+                if let Operand::Register(right) = right {
+                    release(right);
+                }
+            }
+            let dst = free.pop().unwrap_or_else(|| {
+                count += 1;
+                count - 1
+            });
+            registers[index] = dst;
+            ops.push(match op {
+                Op::Input { index, .. } => Op::Input { dst, index },
+                Op::Const { value, .. } => Op::Const { dst, value },
+                Op::Apply {
+                    operation,
+                    left,
+                    right,
+                    ..
+                } => Op::Apply {
+                    operation,
+                    dst,
+                    left: registers[usize::from(left)],
+                    right: match right {
+                        Operand::Register(number) => {
+                            Operand::Register(registers[usize::from(number)])
+                        }
+                        immediate => immediate,
+                    },
+                },
+            });
+        }
+        let output = match output {
+            Operand::Register(number) => Operand::Register(registers[usize::from(number)]),
+            immediate => immediate,
+        };
+        (ops, usize::from(count), output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_file_lines, try_parse_str_lines};
+
+    use super::super::interpreter::Interpreter;
+    use super::*;
+
+    fn compile(program: &str) -> Bytecode {
+        Bytecode::compile(
+            &try_parse_str_lines::<Instruction>(program).unwrap(),
+            Variable::Z,
+        )
+    }
+
+    #[test]
+    fn folds_constants_and_drops_dead_stores() {
+        let bytecode =
+            compile("inp w\nadd x 5\nmul x 0\nadd y w\nadd z 3\nadd z x\nmul z w\ninp x");
+        assert_eq!("r0 = inp 0\nr0 = mul r0 3\nret r0", bytecode.to_string());
+        assert_eq!(Some(21), bytecode.run(&[7, 1]));
+    }
+
+    #[test]
+    fn shared_expressions_are_computed_once() {
+        let bytecode = compile("inp w\nadd w 4\nadd x w\nmul w x\nadd z w\nadd z x");
+        assert_eq!(
+            "r0 = inp 0\nr0 = add r0 4\nr1 = mul r0 r0\nr0 = add r1 r0\nret r0",
+            bytecode.to_string()
+        );
+        assert_eq!(Some(30), bytecode.run(&[1]));
+    }
+
+    #[test]
+    fn agrees_with_the_interpreter() {
+        let program: Vec<Instruction> = parse_file_lines("src/day24/input.txt").collect();
+        let bytecode = Bytecode::compile(&program, Variable::Z);
+        assert!(bytecode.len() < program.len());
+        let mut seed: u64 = 24;
+        for _ in 0..1000 {
+            let digits: Vec<i64> = (0..14)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                    i64::try_from(seed >> 33).unwrap() % 9 + 1
+                })
+                .collect();
+            let mut interpreter = Interpreter::new(digits.iter().copied());
+            interpreter.run(&program).unwrap();
+            assert_eq!(Some(interpreter.get(Variable::Z)), bytecode.run(&digits));
+        }
+        assert!(bytecode.is_valid(39_924_989_499_969));
+        assert!(bytecode.is_valid(16_811_412_161_117));
+        assert!(!bytecode.is_valid(99_999_999_999_999));
+    }
+
+    #[test]
+    fn model_numbers_with_zero_digits_are_invalid() {
+        let program = try_parse_str_lines::<Instruction>("inp w\neql w 0\nadd z w").unwrap();
+        let bytecode = Bytecode::compile(&program, Variable::Z);
+        for model_number in [0, 5] {
+            assert_eq!(
+                crate::day24::is_valid(&program, model_number).unwrap(),
+                bytecode.is_valid(model_number),
+            );
+        }
+        assert!(!bytecode.is_valid(0));
+    }
+}
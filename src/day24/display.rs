@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::expression::Expression;
+
+/// Every distinct node of an expression, identified by address so shared sub-expressions are
+/// only visited once.
+struct Nodes<'a> {
+    /// Operands before the expressions using them
+    order: Vec<&'a Expression>,
+    /// Index in `order` and the number of expressions using the node
+    seen: HashMap<*const Expression, (usize, usize)>,
+}
+
+impl<'a> Nodes<'a> {
+    fn new(root: &'a Expression) -> Self {
+        let mut nodes = Self {
+            order: Vec::new(),
+            seen: HashMap::new(),
+        };
+        nodes.visit(root);
+        nodes
+    }
+
+    fn visit(&mut self, expression: &'a Expression) {
+        if let Some((_, uses)) = self.seen.get_mut(&(expression as *const _)) {
+            *uses += 1;
+            return;
+        }
+        if let Some((left, right, _)) = expression.operands() {
+            self.visit(left);
+            self.visit(right);
+        }
+        self.seen.insert(expression, (self.order.len(), 1));
+        self.order.push(expression);
+    }
+
+    fn index(&self, expression: &Expression) -> usize {
+        self.seen[&(expression as *const _)].0
+    }
+
+    fn is_shared(&self, expression: &Expression) -> bool {
+        self.seen[&(expression as *const _)].1 > 1
+    }
+}
+
+impl Expression {
+    /// Both operands and the operator of an operation
+    fn operands(&self) -> Option<(&Self, &Self, &'static str)> {
+        match self {
+            Self::Value(_) | Self::Input { .. } | Self::Initial(_) => None,
+            Self::Add(left, right) => Some((left, right, "+")),
+            Self::Mul(left, right) => Some((left, right, "*")),
+            Self::Div(left, right) => Some((left, right, "/")),
+            Self::Mod(left, right) => Some((left, right, "%")),
+            Self::Eql(left, right) => Some((left, right, "==")),
+        }
+    }
+
+    /// How tightly the operator binds, operands binding less tightly need parentheses
+    const fn precedence(&self) -> u8 {
+        match self {
+            Self::Eql(..) => 1,
+            Self::Add(..) => 2,
+            Self::Mul(..) | Self::Div(..) | Self::Mod(..) => 3,
+            Self::Value(_) | Self::Input { .. } | Self::Initial(_) => 4,
+        }
+    }
+
+    /// Graphviz graph of the expression, with one node per shared sub-expression
+    pub fn dot(&self) -> Dot<'_> {
+        Dot(self)
+    }
+}
+
+/// Writes `expression` in infix notation, using the names of bound sub-expressions
+fn write_infix(
+    f: &mut fmt::Formatter<'_>,
+    expression: &Expression,
+    names: &HashMap<*const Expression, usize>,
+    bind: bool,
+) -> fmt::Result {
+    if let (true, Some(name)) = (bind, names.get(&(expression as *const _))) {
+        return write!(f, "t{}", name);
+    }
+    match expression {
+        Expression::Value(value) => write!(f, "{}", value),
+        Expression::Input { index } => write!(f, "i{}", index),
+        Expression::Initial(var) => write!(f, "{}", var),
+        _ => {
+            let (left, right, operator) = expression.operands().unwrap();
+            let precedence = expression.precedence();
+            let bound = |operand: &Expression| names.contains_key(&(operand as *const _));
+            let left_parens = !bound(left) && left.precedence() < precedence;
+            let right_parens = !bound(right) && right.precedence() <= precedence;
+            write_operand(f, left, names, left_parens)?;
+            match (expression, right) {
+                // adding a negative number reads better as a subtraction
+                (Expression::Add(..), &Expression::Value(value)) if value < 0 => {
+                    write!(f, " - {}", value.unsigned_abs())
+                }
+                _ => {
+                    write!(f, " {} ", operator)?;
+                    write_operand(f, right, names, right_parens)
+                }
+            }
+        }
+    }
+}
+
+fn write_operand(
+    f: &mut fmt::Formatter<'_>,
+    operand: &Expression,
+    names: &HashMap<*const Expression, usize>,
+    parens: bool,
+) -> fmt::Result {
+    if parens {
+        f.write_str("(")?;
+        write_infix(f, operand, names, true)?;
+        f.write_str(")")
+    } else {
+        write_infix(f, operand, names, true)
+    }
+}
+
+/// Infix notation, sub-expressions used more than once are printed once as `let` bindings
+/// named `t0`, `t1`... Inputs are `i0`, `i1`... and initial register values their name.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = Nodes::new(self);
+        let mut names = HashMap::new();
+        for &node in &nodes.order {
+            if node.operands().is_some() && nodes.is_shared(node) {
+                write!(f, "let t{} = ", names.len())?;
+                write_infix(f, node, &names, false)?;
+                writeln!(f)?;
+                names.insert(node as *const _, names.len());
+            }
+        }
+        write_infix(f, self, &names, true)
+    }
+}
+
+/// Graphviz DOT export of an [`Expression`], render with `dot -Tsvg`.
+///
+/// Operands are drawn left to right below the operation using them.
+pub struct Dot<'a>(&'a Expression);
+
+impl fmt::Display for Dot<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes = Nodes::new(self.0);
+        writeln!(f, "digraph expression {{")?;
+        writeln!(f, "    ordering=out;")?;
+        for (index, node) in nodes.order.iter().enumerate() {
+            let (label, shape) = match node {
+                Expression::Value(value) => (value.to_string(), "plaintext"),
+                Expression::Input { index } => (format!("i{}", index), "box"),
+                Expression::Initial(var) => (var.to_string(), "box"),
+                _ => (node.operands().unwrap().2.to_string(), "circle"),
+            };
+            writeln!(f, "    n{} [label=\"{}\", shape={}];", index, label, shape)?;
+        }
+        for (index, node) in nodes.order.iter().enumerate() {
+            if let Some((left, right, _)) = node.operands() {
+                writeln!(f, "    n{} -> n{};", index, nodes.index(left))?;
+                writeln!(f, "    n{} -> n{};", index, nodes.index(right))?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::try_parse_str_lines;
+
+    use super::super::alu::Alu;
+    use super::super::input::{Instruction, Variable};
+    use super::*;
+
+    fn z_of(program: &str) -> Expression {
+        let alu = Alu::default().run(&try_parse_str_lines::<Instruction>(program).unwrap());
+        (*alu[Variable::Z].expression).clone()
+    }
+
+    #[test]
+    fn infix_uses_as_few_parentheses_as_possible() {
+        let z =
+            z_of("inp x\ninp y\nadd x y\nmul x 3\nadd z 10\ndiv z x\nadd z y\nmod z 7\neql z 1");
+        assert_eq!("(10 / ((i0 + i1) * 3) + i1) % 7 == 1", z.to_string());
+        let negated = z_of("inp z\nadd z -4\nmul z -1");
+        assert_eq!("(i0 - 4) * -1", negated.to_string());
+    }
+
+    #[test]
+    fn shared_expressions_are_bound_once() {
+        let z = z_of("inp w\nadd w 4\nadd x w\nmul w x\nadd z w\nadd z x");
+        assert_eq!("let t0 = i0 + 4\nt0 * t0 + t0", z.to_string());
+    }
+
+    #[test]
+    fn initial_registers_are_named() {
+        let alu = Alu::unknown().run(&try_parse_str_lines::<Instruction>("add z 1").unwrap());
+        assert_eq!("z + 1", alu[Variable::Z].expression.to_string());
+    }
+
+    #[test]
+    fn dot_has_a_node_per_shared_expression() {
+        let z = z_of("inp w\nadd w 4\nadd x w\nmul w x\nadd z w\nadd z x");
+        assert_eq!(
+            "digraph expression {\n    ordering=out;\n    \
+            n0 [label=\"i0\", shape=box];\n    \
+            n1 [label=\"4\", shape=plaintext];\n    \
+            n2 [label=\"+\", shape=circle];\n    \
+            n3 [label=\"*\", shape=circle];\n    \
+            n4 [label=\"+\", shape=circle];\n    \
+            n2 -> n0;\n    n2 -> n1;\n    n3 -> n2;\n    n3 -> n2;\n    \
+            n4 -> n3;\n    n4 -> n2;\n}",
+            z.dot().to_string()
+        );
+    }
+}
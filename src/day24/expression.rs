@@ -0,0 +1,110 @@
+use std::rc::Rc;
+
+use super::input::Variable;
+use super::range::Range;
+
+/// Value of a register in terms of the program inputs, sub-expressions are shared between
+/// registers instead of copied.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expression {
+    Value(i64),
+    Input {
+        index: u8,
+    },
+    /// The register's value before the first instruction
+    Initial(Variable),
+    Add(Rc<Expression>, Rc<Expression>),
+    Mul(Rc<Expression>, Rc<Expression>),
+    Div(Rc<Expression>, Rc<Expression>),
+    Mod(Rc<Expression>, Rc<Expression>),
+    Eql(Rc<Expression>, Rc<Expression>),
+}
+
+impl Expression {
+    pub const ZERO: Expression = Expression::Value(0);
+    pub const ONE: Expression = Expression::Value(1);
+
+    /// `None` when the ALU would fail: dividing by zero, a modulo with a negative dividend or a
+    /// divisor that is not positive, or overflowing `i64`
+    pub fn evaluate(&self, initial: &[i64; 4], inputs: &[i64]) -> Option<i64> {
+        let operands = |left: &Self, right: &Self| {
+            Some((
+                left.evaluate(initial, inputs)?,
+                right.evaluate(initial, inputs)?,
+            ))
+        };
+        match self {
+            Self::Value(value) => Some(*value),
+            Self::Input { index } => inputs.get(usize::from(*index)).copied(),
+            Self::Initial(var) => Some(initial[var.index()]),
+            Self::Add(left, right) => {
+                operands(left, right).and_then(|(left, right)| left.checked_add(right))
+            }
+            Self::Mul(left, right) => {
+                operands(left, right).and_then(|(left, right)| left.checked_mul(right))
+            }
+            Self::Div(left, right) => {
+                operands(left, right).and_then(|(left, right)| left.checked_div(right))
+            }
+            Self::Mod(left, right) => operands(left, right)
+                .filter(|&(left, right)| left >= 0 && right > 0)
+                .map(|(left, right)| left % right),
+            Self::Eql(left, right) => {
+                operands(left, right).map(|(left, right)| i64::from(left == right))
+            }
+        }
+    }
+
+    /// Values this expression can take when registers start in `initial` and inputs in `inputs`
+    pub fn range(&self, initial: &[Range; 4], inputs: &[Range]) -> Range {
+        let operands =
+            |left: &Self, right: &Self| (left.range(initial, inputs), right.range(initial, inputs));
+        match self {
+            Self::Value(value) => Range::constant(*value),
+            Self::Input { index } => inputs
+                .get(usize::from(*index))
+                .copied()
+                .unwrap_or(Range::FULL),
+            Self::Initial(var) => initial[var.index()],
+            Self::Add(left, right) => {
+                let (left, right) = operands(left, right);
+                left + right
+            }
+            Self::Mul(left, right) => {
+                let (left, right) = operands(left, right);
+                left * right
+            }
+            Self::Div(left, right) => {
+                let (left, right) = operands(left, right);
+                left / right
+            }
+            Self::Mod(left, right) => {
+                let (left, right) = operands(left, right);
+                left % right
+            }
+            Self::Eql(left, right) => {
+                let (left, right) = operands(left, right);
+                left.eql(right)
+            }
+        }
+    }
+
+    /// Whether the expression depends on the initial value of `var`
+    pub fn reads(&self, var: Variable) -> bool {
+        match self {
+            Self::Value(_) | Self::Input { .. } => false,
+            Self::Initial(initial) => *initial == var,
+            Self::Add(left, right)
+            | Self::Mul(left, right)
+            | Self::Div(left, right)
+            | Self::Mod(left, right)
+            | Self::Eql(left, right) => left.reads(var) || right.reads(var),
+        }
+    }
+}
+
+impl Default for Expression {
+    fn default() -> Self {
+        Expression::Value(0)
+    }
+}
@@ -1,5 +1,8 @@
+use std::fmt;
 use std::str::FromStr;
 
+use crate::{parse_part, Error};
+
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
 pub enum Variable {
     W,
@@ -8,6 +11,20 @@ pub enum Variable {
     Z,
 }
 
+impl Variable {
+    pub const ALL: [Self; 4] = [Self::W, Self::X, Self::Y, Self::Z];
+
+    /// Position in [`Variable::ALL`]
+    pub const fn index(self) -> usize {
+        match self {
+            Self::W => 0,
+            Self::X => 1,
+            Self::Y => 2,
+            Self::Z => 3,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
 pub enum Value {
     Variable(Variable),
@@ -25,18 +42,18 @@ pub enum Instruction {
 }
 
 impl FromStr for Value {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        Ok(match input.parse() {
-            Ok(number) => Self::Number(number),
-            Err(_) => Self::Variable(input.parse().unwrap()),
-        })
+        match input.parse() {
+            Ok(number) => Ok(Self::Number(number)),
+            Err(_) => input.parse().map(Self::Variable),
+        }
     }
 }
 
 impl FromStr for Variable {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
@@ -44,27 +61,122 @@ impl FromStr for Variable {
             "x" => Ok(Variable::X),
             "y" => Ok(Variable::Y),
             "z" => Ok(Variable::Z),
-            _ => panic!("Invalid variable: {}", s),
+            _ => Err(Error::new(format!("invalid variable '{}'", s))),
         }
     }
 }
 
 impl FromStr for Instruction {
-    type Err = ();
+    type Err = Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let mut parts = input.split_whitespace();
-        let instruction = parts.next().unwrap();
-        let variable = parts.next().unwrap().parse().unwrap();
-        let value = parts.next().map(|v| v.parse().unwrap());
-        Ok(match instruction {
-            "inp" => Self::Inp(variable),
-            "add" => Self::Add(variable, value.unwrap()),
-            "mul" => Self::Mul(variable, value.unwrap()),
-            "div" => Self::Div(variable, value.unwrap()),
-            "mod" => Self::Mod(variable, value.unwrap()),
-            "eql" => Self::Eql(variable, value.unwrap()),
-            _ => panic!("Invalid instruction: {}", instruction),
+        let mut next = |what: &str| {
+            parts
+                .next()
+                .ok_or_else(|| Error::at_end(input, format!("expected {}", what)))
+        };
+        let instruction = next("an instruction")?;
+        let binary: Option<fn(Variable, Value) -> Self> = match instruction {
+            "inp" => None,
+            "add" => Some(Self::Add),
+            "mul" => Some(Self::Mul),
+            "div" => Some(Self::Div),
+            "mod" => Some(Self::Mod),
+            "eql" => Some(Self::Eql),
+            _ => {
+                return Err(Error::at(
+                    input,
+                    instruction,
+                    format!("invalid instruction '{}'", instruction),
+                ))
+            }
+        };
+        let variable = parse_part(input, next("a variable")?)?;
+        let instruction = match binary {
+            None => Self::Inp(variable),
+            Some(binary) => binary(variable, parse_part(input, next("a value")?)?),
+        };
+        match parts.next() {
+            Some(extra) => Err(Error::at(
+                input,
+                extra,
+                "expected the end of the instruction",
+            )),
+            None => Ok(instruction),
+        }
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::W => "w",
+            Self::X => "x",
+            Self::Y => "y",
+            Self::Z => "z",
         })
     }
 }
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Variable(var) => write!(f, "{}", var),
+            Self::Number(number) => write!(f, "{}", number),
+        }
+    }
+}
+
+/// Same syntax as the puzzle input
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inp(var) => write!(f, "inp {}", var),
+            Self::Add(var, val) => write!(f, "add {} {}", var, val),
+            Self::Mul(var, val) => write!(f, "mul {} {}", var, val),
+            Self::Div(var, val) => write!(f, "div {} {}", var, val),
+            Self::Mod(var, val) => write!(f, "mod {} {}", var, val),
+            Self::Eql(var, val) => write!(f, "eql {} {}", var, val),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_instructions() {
+        assert_eq!(Ok(Instruction::Inp(Variable::W)), "inp w".parse());
+        assert_eq!(
+            Ok(Instruction::Add(Variable::X, Value::Number(-1))),
+            "add x -1".parse()
+        );
+        assert_eq!(
+            Ok(Instruction::Eql(Variable::Y, Value::Variable(Variable::Z))),
+            "eql y z".parse()
+        );
+    }
+
+    #[test]
+    fn invalid_instructions_are_errors() {
+        let error = "foo x".parse::<Instruction>().unwrap_err();
+        assert_eq!(
+            "line 1, column 1: invalid instruction 'foo'",
+            error.to_string()
+        );
+        let error = "add x 1 junk".parse::<Instruction>().unwrap_err();
+        assert_eq!(
+            "line 1, column 9: expected the end of the instruction",
+            error.to_string()
+        );
+        let error = "inp w x".parse::<Instruction>().unwrap_err();
+        assert_eq!(
+            "line 1, column 7: expected the end of the instruction",
+            error.to_string()
+        );
+        let error = "mul x".parse::<Instruction>().unwrap_err();
+        assert_eq!("line 1, column 6: expected a value", error.to_string());
+    }
+}
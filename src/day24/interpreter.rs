@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use super::input::{Instruction, Value, Variable};
+use crate::Error;
+
+/// Runs instructions on concrete values.
+///
+/// Errors are placed on the line of the failing instruction, counting from 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Interpreter {
+    /// In [`Variable::ALL`] order
+    pub registers: [i64; 4],
+    inputs: VecDeque<i64>,
+}
+
+/// State right after executing one instruction, reported in trace mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub line: usize,
+    pub instruction: Instruction,
+    pub registers: [i64; 4],
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [w, x, y, z] = self.registers;
+        write!(
+            f,
+            "{:>4}: {:<10} w={} x={} y={} z={}",
+            self.line,
+            self.instruction.to_string(),
+            w,
+            x,
+            y,
+            z
+        )
+    }
+}
+
+impl Interpreter {
+    /// Registers start at zero, `inp` instructions read from `inputs` in order
+    pub fn new(inputs: impl IntoIterator<Item = i64>) -> Self {
+        Self {
+            registers: [0; 4],
+            inputs: inputs.into_iter().collect(),
+        }
+    }
+
+    /// Digits of `model_number` as inputs, most significant first
+    pub fn for_model_number(model_number: u64) -> Self {
+        let digits = model_number.to_string();
+        Self::new(digits.bytes().map(|digit| i64::from(digit - b'0')))
+    }
+
+    pub fn push_input(&mut self, input: i64) {
+        self.inputs.push_back(input);
+    }
+
+    pub const fn get(&self, var: Variable) -> i64 {
+        self.registers[var.index()]
+    }
+
+    pub fn run(&mut self, program: &[Instruction]) -> Result<(), Error> {
+        self.run_traced(program, |_| {})
+    }
+
+    /// Like [`Interpreter::run`], calling `trace` after every instruction
+    pub fn run_traced(
+        &mut self,
+        program: &[Instruction],
+        mut trace: impl FnMut(&Step),
+    ) -> Result<(), Error> {
+        for (index, &instruction) in program.iter().enumerate() {
+            let line = index + 1;
+            self.execute(instruction).map_err(|err| err.on_line(line))?;
+            trace(&Step {
+                line,
+                instruction,
+                registers: self.registers,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) -> Result<(), Error> {
+        let fail = |message: &str| Error::new(format!("{} in '{}'", message, instruction));
+        let overflow = || fail("overflow");
+        let (var, result) = match instruction {
+            Instruction::Inp(var) => {
+                let input = self
+                    .inputs
+                    .pop_front()
+                    .ok_or_else(|| fail("no input left"))?;
+                (var, input)
+            }
+            Instruction::Add(var, val) => {
+                let result = self.get(var).checked_add(self.value(val));
+                (var, result.ok_or_else(overflow)?)
+            }
+            Instruction::Mul(var, val) => {
+                let result = self.get(var).checked_mul(self.value(val));
+                (var, result.ok_or_else(overflow)?)
+            }
+            Instruction::Div(var, val) => match self.value(val) {
+                0 => return Err(fail("division by zero")),
+                right => (var, self.get(var).checked_div(right).ok_or_else(overflow)?),
+            },
+            Instruction::Mod(var, val) => match (self.get(var), self.value(val)) {
+                (_, 0) => return Err(fail("modulo by zero")),
+                (left, right) if left < 0 || right < 0 => {
+                    return Err(fail(&format!("modulo of {} by {}", left, right)))
+                }
+                (left, right) => (var, left % right),
+            },
+            Instruction::Eql(var, val) => (var, i64::from(self.get(var) == self.value(val))),
+        };
+        self.registers[var.index()] = result;
+        Ok(())
+    }
+
+    fn value(&self, val: Value) -> i64 {
+        match val {
+            Value::Variable(var) => self.get(var),
+            Value::Number(number) => i64::from(number),
+        }
+    }
+}
+
+/// Whether MONAD accepts `model_number`, that is ends with `z` equal to 0
+pub fn is_valid(program: &[Instruction], model_number: u64) -> Result<bool, Error> {
+    let mut interpreter = Interpreter::for_model_number(model_number);
+    interpreter.run(program)?;
+    Ok(interpreter.get(Variable::Z) == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_file_lines, try_parse_str_lines};
+
+    use super::*;
+
+    fn program(input: &str) -> Vec<Instruction> {
+        try_parse_str_lines(input).unwrap()
+    }
+
+    #[test]
+    fn binary_conversion_example() {
+        let program = program(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\n\
+            div w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        );
+        let mut interpreter = Interpreter::new([11]);
+        interpreter.run(&program).unwrap();
+        assert_eq!([1, 0, 1, 1], interpreter.registers);
+    }
+
+    #[test]
+    fn errors_name_the_failing_line() {
+        let program = program("inp x\ninp y\nmod x y");
+        let error = Interpreter::new([5, 0]).run(&program).unwrap_err();
+        assert_eq!("line 3: modulo by zero in 'mod x y'", error.to_string());
+        let error = Interpreter::new([-5, 3]).run(&program).unwrap_err();
+        assert_eq!("line 3: modulo of -5 by 3 in 'mod x y'", error.to_string());
+        let error = Interpreter::new([1]).run(&program).unwrap_err();
+        assert_eq!("line 2: no input left in 'inp y'", error.to_string());
+        let error = Interpreter::default()
+            .execute(Instruction::Div(Variable::X, Value::Number(0)))
+            .unwrap_err();
+        assert_eq!("division by zero in 'div x 0'", error.to_string());
+    }
+
+    #[test]
+    fn trace_reports_every_step() {
+        let program = program("inp x\nmul x -1");
+        let mut steps = Vec::new();
+        Interpreter::new([3])
+            .run_traced(&program, |step| steps.push(step.to_string()))
+            .unwrap();
+        assert_eq!(
+            vec![
+                "   1: inp x      w=0 x=3 y=0 z=0",
+                "   2: mul x -1   w=0 x=-3 y=0 z=0"
+            ],
+            steps
+        );
+    }
+
+    #[test]
+    fn solved_model_numbers_are_valid() {
+        let program: Vec<Instruction> = parse_file_lines("src/day24/input.txt").collect();
+        assert_eq!(Ok(true), is_valid(&program, 39_924_989_499_969));
+        assert_eq!(Ok(true), is_valid(&program, 16_811_412_161_117));
+        assert_eq!(Ok(false), is_valid(&program, 99_999_999_999_999));
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use super::alu::Alu;
+use super::expression::Expression;
+use super::input::{Instruction, Variable};
+use super::range::Range;
+
+/// A program split into one block per input, searched digit by digit for valid model numbers.
+///
+/// A model number is valid when the program ends with `z` equal to 0.
+#[derive(Clone, Debug)]
+pub struct Monad {
+    blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+    /// Registers after the block in terms of the registers before it and its input
+    registers: [Rc<Expression>; 4],
+    /// Registers whose value before the block matters for the rest of the program
+    live: [bool; 4],
+}
+
+impl Block {
+    fn run(&self, registers: &[i64; 4], digit: i64) -> Option<[i64; 4]> {
+        let mut next = [0; 4];
+        for (value, expression) in next.iter_mut().zip(&self.registers) {
+            *value = expression.evaluate(registers, &[digit])?;
+        }
+        Some(next)
+    }
+}
+
+/// Blocks already known not to lead to a valid model number, by index and registers
+type Failed = HashSet<(usize, [i64; 4])>;
+
+impl Monad {
+    pub fn new(instructions: &[Instruction]) -> Self {
+        let mut starts: Vec<usize> = instructions
+            .iter()
+            .enumerate()
+            .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)))
+            .map(|(index, _)| index)
+            .collect();
+        // instructions before the first input run as part of the first block
+        match starts.first_mut() {
+            Some(first) => *first = 0,
+            // model numbers have a digit per input, so there are none to search
+            None => return Self { blocks: Vec::new() },
+        }
+        starts.push(instructions.len());
+        let mut blocks: Vec<Block> = starts
+            .windows(2)
+            .map(|range| {
+                let alu = Alu::unknown().run(&instructions[range[0]..range[1]]);
+                Block {
+                    registers: alu.registers().map(|register| register.expression.clone()),
+                    live: [true; 4],
+                }
+            })
+            .collect();
+        let mut live_after = Variable::ALL.map(|var| var == Variable::Z);
+        for block in blocks.iter_mut().rev() {
+            block.live = Variable::ALL.map(|var| {
+                Variable::ALL.iter().any(|output| {
+                    live_after[output.index()] && block.registers[output.index()].reads(var)
+                })
+            });
+            live_after = block.live;
+        }
+        Self { blocks }
+    }
+
+    pub fn largest(&self) -> Option<u64> {
+        self.search(&[9, 8, 7, 6, 5, 4, 3, 2, 1])
+    }
+
+    pub fn smallest(&self) -> Option<u64> {
+        self.search(&[1, 2, 3, 4, 5, 6, 7, 8, 9])
+    }
+
+    /// First valid model number when trying `digits` in order at every position
+    fn search(&self, digits: &[i64]) -> Option<u64> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let mut model_number = Vec::with_capacity(self.blocks.len());
+        let mut failed = Failed::new();
+        if self.search_from(0, [0; 4], digits, &mut failed, &mut model_number) {
+            Some(model_number.iter().fold(0, |number, &digit| {
+                number * 10 + u64::try_from(digit).unwrap()
+            }))
+        } else {
+            None
+        }
+    }
+
+    fn search_from(
+        &self,
+        index: usize,
+        registers: [i64; 4],
+        digits: &[i64],
+        failed: &mut Failed,
+        model_number: &mut Vec<i64>,
+    ) -> bool {
+        let block = match self.blocks.get(index) {
+            Some(block) => block,
+            None => return registers[Variable::Z.index()] == 0,
+        };
+        let mut registers = registers;
+        for (value, &live) in registers.iter_mut().zip(&block.live) {
+            if !live {
+                *value = 0;
+            }
+        }
+        if failed.contains(&(index, registers)) || !self.can_finish(index, &registers) {
+            return false;
+        }
+        for &digit in digits {
+            if let Some(next) = block.run(&registers, digit) {
+                model_number.push(digit);
+                if self.search_from(index + 1, next, digits, failed, model_number) {
+                    return true;
+                }
+                model_number.pop();
+            }
+        }
+        failed.insert((index, registers));
+        false
+    }
+
+    /// Range analysis of the blocks from `index` on, false when `z` can no longer end up 0
+    fn can_finish(&self, index: usize, registers: &[i64; 4]) -> bool {
+        let mut ranges = registers.map(Range::constant);
+        for block in &self.blocks[index..] {
+            ranges = block
+                .registers
+                .each_ref()
+                .map(|expression| expression.range(&ranges, &[Range::DIGIT]));
+        }
+        ranges[Variable::Z.index()].contains(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::try_parse_str_lines;
+
+    use super::*;
+
+    /// Valid when the second digit is the first one plus 2
+    const PROGRAM: &str = "\
+        inp z\n\
+        add z 2\n\
+        inp w\n\
+        eql z w\n\
+        eql z 0\
+    ";
+
+    #[test]
+    fn finds_largest_and_smallest() {
+        let monad = Monad::new(&try_parse_str_lines::<Instruction>(PROGRAM).unwrap());
+        assert_eq!(Some(79), monad.largest());
+        assert_eq!(Some(13), monad.smallest());
+    }
+
+    #[test]
+    fn only_live_registers_are_kept_between_blocks() {
+        let monad = Monad::new(&try_parse_str_lines::<Instruction>(PROGRAM).unwrap());
+        assert_eq!([false; 4], monad.blocks[0].live);
+        assert_eq!([false, false, false, true], monad.blocks[1].live);
+    }
+
+    #[test]
+    fn impossible_program_has_no_model_number() {
+        let program = try_parse_str_lines::<Instruction>("inp z\nadd z 1").unwrap();
+        assert_eq!(None, Monad::new(&program).largest());
+    }
+
+    #[test]
+    fn program_without_inputs_has_no_model_number() {
+        let program = try_parse_str_lines::<Instruction>("add x 1").unwrap();
+        assert_eq!(None, Monad::new(&program).largest());
+        assert_eq!(None, Monad::new(&[]).smallest());
+    }
+
+    #[test]
+    fn failing_programs_have_no_model_number() {
+        for program in ["inp w\nmod w -3", "inp w\ndiv w 0"] {
+            let program = try_parse_str_lines::<Instruction>(program).unwrap();
+            assert_eq!(None, Monad::new(&program).largest());
+        }
+    }
+
+    #[test]
+    fn failing_expressions_are_not_folded_away() {
+        for program in [
+            "inp w\ndiv w 0\nmul w 0\nadd z w",
+            "inp w\nmod w 0\neql w w\nadd z w\nadd z -1",
+        ] {
+            let program = try_parse_str_lines::<Instruction>(program).unwrap();
+            for digit in 1..=9 {
+                assert!(crate::day24::is_valid(&program, digit).is_err());
+            }
+            assert_eq!(None, Monad::new(&program).largest());
+            assert_eq!(None, Monad::new(&program).smallest());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::cmp::{max, min};
+use std::ops::{Add, Div, Mul, Rem};
+
+/// Inclusive bounds on the values an expression can take, saturating at the `i64` limits.
+#[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
+pub struct Range {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Range {
+    pub const FULL: Self = Self::new(i64::MIN, i64::MAX);
+    /// Model number digits
+    pub const DIGIT: Self = Self::new(1, 9);
+
+    pub const fn new(min: i64, max: i64) -> Self {
+        Self { min, max }
+    }
+
+    pub const fn constant(value: i64) -> Self {
+        Self::new(value, value)
+    }
+
+    pub const fn as_constant(self) -> Option<i64> {
+        if self.min == self.max {
+            Some(self.min)
+        } else {
+            None
+        }
+    }
+
+    pub const fn contains(self, value: i64) -> bool {
+        self.min <= value && value <= self.max
+    }
+
+    pub const fn is_within(self, other: Self) -> bool {
+        other.min <= self.min && self.max <= other.max
+    }
+
+    const fn is_disjoint(self, other: Self) -> bool {
+        self.max < other.min || other.max < self.min
+    }
+
+    fn union(self, other: Self) -> Self {
+        Self::new(min(self.min, other.min), max(self.max, other.max))
+    }
+
+    /// Smallest range containing `f` applied to every pair of bounds
+    fn corners(self, other: Self, f: impl Fn(i64, i64) -> i64) -> Self {
+        let values = [
+            f(self.min, other.min),
+            f(self.min, other.max),
+            f(self.max, other.min),
+            f(self.max, other.max),
+        ];
+        Self::new(
+            values.into_iter().min().unwrap(),
+            values.into_iter().max().unwrap(),
+        )
+    }
+
+    /// 1 when both sides are always equal, 0 when they never are
+    pub fn eql(self, other: Self) -> Self {
+        match (self.as_constant(), other.as_constant()) {
+            (Some(left), Some(right)) if left == right => Self::constant(1),
+            _ if self.is_disjoint(other) => Self::constant(0),
+            _ => Self::new(0, 1),
+        }
+    }
+}
+
+impl Add for Range {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.min.saturating_add(other.min),
+            self.max.saturating_add(other.max),
+        )
+    }
+}
+
+impl Mul for Range {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.corners(other, i64::saturating_mul)
+    }
+}
+
+impl Div for Range {
+    type Output = Self;
+
+    /// Division truncating towards zero, a divisor that can only be zero gives [`Range::FULL`]
+    fn div(self, other: Self) -> Self {
+        let negative = Self::new(other.min, min(other.max, -1));
+        let positive = Self::new(max(other.min, 1), other.max);
+        match (negative.min <= negative.max, positive.min <= positive.max) {
+            (true, true) => self
+                .corners(negative, i64::saturating_div)
+                .union(self.corners(positive, i64::saturating_div)),
+            (true, false) => self.corners(negative, i64::saturating_div),
+            (false, true) => self.corners(positive, i64::saturating_div),
+            (false, false) => Self::FULL,
+        }
+    }
+}
+
+impl Rem for Range {
+    type Output = Self;
+
+    /// Remainder with the sign of the dividend, a divisor that can only be zero gives
+    /// [`Range::FULL`]
+    fn rem(self, other: Self) -> Self {
+        if let (Some(left), Some(right)) = (self.as_constant(), other.as_constant()) {
+            return left.checked_rem(right).map_or(Self::FULL, Self::constant);
+        }
+        if other == Self::constant(0) {
+            return Self::FULL;
+        }
+        let largest = max(other.min.unsigned_abs(), other.max.unsigned_abs()) - 1;
+        let largest = i64::try_from(largest).unwrap_or(i64::MAX);
+        Self::new(
+            if self.min >= 0 {
+                0
+            } else {
+                max(self.min, -largest)
+            },
+            if self.max <= 0 {
+                0
+            } else {
+                min(self.max, largest)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_keeps_all_values() {
+        let digit = Range::DIGIT;
+        assert_eq!(Range::new(11, 19), digit + Range::constant(10));
+        assert_eq!(Range::new(-18, -2), digit * Range::constant(-2));
+        assert_eq!(Range::new(0, 3), digit / Range::constant(3));
+        assert_eq!(Range::new(-9, 9), digit / Range::new(-1, 1));
+        assert_eq!(Range::new(0, 9), digit % Range::constant(26));
+        assert_eq!(Range::new(0, 4), digit % Range::constant(5));
+    }
+
+    #[test]
+    fn eql_of_disjoint_ranges_is_zero() {
+        let digit = Range::DIGIT;
+        assert_eq!(Range::constant(0), digit.eql(Range::new(10, 35)));
+        assert_eq!(Range::new(0, 1), digit.eql(Range::new(9, 35)));
+        assert_eq!(
+            Range::constant(1),
+            Range::constant(4).eql(Range::constant(4))
+        );
+    }
+
+    #[test]
+    fn saturates_instead_of_overflowing() {
+        let huge = Range::new(0, i64::MAX);
+        assert_eq!(huge, huge + huge);
+        assert_eq!(Range::new(i64::MIN, i64::MAX), huge * Range::new(-2, 2));
+    }
+}
@@ -0,0 +1,148 @@
+use std::convert::Infallible;
+use std::num::ParseIntError;
+use std::{error, fmt, io};
+
+/// Error produced while reading or parsing puzzle input.
+///
+/// Lines and columns are 1-based and count characters, not bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// Error located at the start of `part`, which must be a slice of `input`
+    pub fn at(input: &str, part: &str, message: impl Into<String>) -> Self {
+        Self::new(message).within(input, part)
+    }
+
+    /// Error located just after the last character of `input`
+    pub fn at_end(input: &str, message: impl Into<String>) -> Self {
+        Self::at(input, &input[input.len()..], message)
+    }
+
+    /// Translates a position relative to `part` into one relative to `input`.
+    ///
+    /// `part` must be a slice of `input`, errors without a position are placed at the start of
+    /// `part`.
+    pub fn within(mut self, input: &str, part: &str) -> Self {
+        if let Some((line, column)) = locate(input, part) {
+            match (self.line, self.column) {
+                (None | Some(1), column_in_part) => {
+                    self.line = Some(line);
+                    self.column = Some(column + column_in_part.unwrap_or(1) - 1);
+                }
+                (Some(line_in_part), _) => self.line = Some(line + line_in_part - 1),
+            }
+        }
+        self
+    }
+
+    /// Places an error from parsing a single line at line `line` of the whole input
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = Some(line + self.line.unwrap_or(1) - 1);
+        self
+    }
+
+    pub const fn for_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+/// Line and column of the start of `part` within `input`
+fn locate(input: &str, part: &str) -> Option<(usize, usize)> {
+    let offset = (part.as_ptr() as usize).checked_sub(input.as_ptr() as usize)?;
+    let before = input.get(..offset)?;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    Some((line, column))
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}: ", day)?;
+        }
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            _ => {}
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::new(err.to_string())
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Self::new(err.to_string())
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_locates_part_in_input() {
+        let input = "abc\ndef\nghi";
+        let error = Error::at(input, &input[9..], "oops");
+        assert_eq!((Some(3), Some(2)), (error.line, error.column));
+    }
+
+    #[test]
+    fn within_shifts_nested_positions() {
+        let input = "header\n\nfirst\nsecond x";
+        let block = &input[8..];
+        let error = Error::at(block, &block[13..], "oops").within(input, block);
+        assert_eq!((Some(4), Some(8)), (error.line, error.column));
+    }
+
+    #[test]
+    fn within_shifts_columns_on_first_line() {
+        let input = "a -> bc";
+        let part = &input[5..];
+        let error = Error::at(part, &part[1..], "oops").within(input, part);
+        assert_eq!((Some(1), Some(7)), (error.line, error.column));
+    }
+
+    #[test]
+    fn display_includes_day_line_and_column() {
+        let error = Error::at("12\n3x", &"12\n3x"[4..], "invalid digit")
+            .on_line(5)
+            .for_day(9);
+        assert_eq!("day 9: line 6, column 2: invalid digit", error.to_string());
+    }
+
+    #[test]
+    fn display_without_position_is_the_message() {
+        assert_eq!("oops", Error::new("oops").to_string());
+    }
+}
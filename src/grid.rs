@@ -0,0 +1,283 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::Error;
+
+/// `(x, y)`, with `(0, 0)` the top left cell
+pub type Position = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// Rectangular grid stored row by row in a single `Vec`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut f)
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parses one cell per character, `f` returns `None` for characters that are not valid cells.
+    ///
+    /// All lines must have the same length, errors name the cell as `what`.
+    pub fn parse_chars(
+        input: &str,
+        what: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Error> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for line in input.lines() {
+            let mut line_width = 0;
+            for (index, c) in line.char_indices() {
+                let cell = f(c).ok_or_else(|| {
+                    Error::at(input, &line[index..], format!("invalid {} '{}'", what, c))
+                })?;
+                cells.push(cell);
+                line_width += 1;
+            }
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(Error::at(
+                        input,
+                        line,
+                        format!("expected {} columns, found {}", width, line_width),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                cells,
+                width,
+                height,
+            }),
+            _ => Err(Error::at(input, input, "empty grid")),
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub const fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// All positions, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The up to 4 positions above, right of, below and left of `position`
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> {
+        self.offsets(position, &ORTHOGONAL)
+    }
+
+    /// The up to 8 positions around `position`, including diagonals
+    pub fn surrounding(&self, position: Position) -> impl Iterator<Item = Position> {
+        self.offsets(position, &SURROUNDING)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn offsets(
+        &self,
+        (x, y): Position,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let x = x.checked_add_signed(dx).filter(|&x| x < width)?;
+            let y = y.checked_add_signed(dy).filter(|&y| y < height)?;
+            Some((x, y))
+        })
+    }
+
+    fn index_of(&self, position @ (x, y): Position) -> Option<usize> {
+        self.contains(position).then(|| y * self.width + x)
+    }
+}
+
+impl<T: From<u8>> Grid<T> {
+    /// Parses a map of single digits, like `2199943210`
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn parse_digits(input: &str, what: &str) -> Result<Self, Error> {
+        Self::parse_chars(input, what, |c| {
+            c.to_digit(10).map(|digit| T::from(digit as u8))
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position)
+            .unwrap_or_else(|| panic!("position {:?} out of bounds", position))
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("position {:?} out of bounds", position))
+    }
+}
+
+/// One line per row, cells written next to each other
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display_digits() {
+        let grid: Grid<u8> = Grid::parse_digits("123\n456\n", "digit").unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(6, grid[(2, 1)]);
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!("123\n456\n", grid.to_string());
+    }
+
+    #[test]
+    fn ragged_rows_are_an_error() {
+        let error = Grid::<u8>::parse_digits("123\n45", "digit").unwrap_err();
+        assert_eq!(
+            "line 2, column 1: expected 3 columns, found 2",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_cells_are_positioned() {
+        let error = Grid::parse_chars("#.\n.x", "pixel", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap_err();
+        assert_eq!("line 2, column 2: invalid pixel 'x'", error.to_string());
+    }
+
+    #[test]
+    fn neighbours_stay_inside_the_grid() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            vec![(1, 0), (0, 1)],
+            grid.neighbours((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.surrounding((1, 1)).count());
+        assert_eq!(3, grid.surrounding((2, 2)).count());
+    }
+}
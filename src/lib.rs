@@ -1,4 +1,8 @@
+pub use error::Error;
+pub use grid::Grid;
 pub use parse::*;
+pub use solution::*;
+pub use verify::{Expectation, Manifest, Outcome, Report};
 
 #[macro_use]
 mod array;
@@ -26,4 +30,9 @@ pub mod day21;
 pub mod day22;
 pub mod day23;
 pub mod day24;
+mod error;
+mod grid;
 mod parse;
+pub mod search;
+mod solution;
+mod verify;
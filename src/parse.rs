@@ -1,27 +1,179 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read};
 use std::str::FromStr;
-use std::{fmt, fs};
 
-pub fn parse_file<E: fmt::Debug, F: FromStr<Err = E>>(filename: &str) -> F {
-    fs::read_to_string(filename)
-        .unwrap()
-        .trim()
-        .parse()
-        .unwrap()
+use crate::Error;
+
+pub fn parse_file<F: FromStr>(filename: &str) -> F
+where
+    F::Err: Into<Error>,
+{
+    try_parse_file(filename).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn parse_file_lines<F: FromStr>(filename: &str) -> impl Iterator<Item = F>
+where
+    F::Err: Into<Error>,
+{
+    try_parse_file_lines(filename)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .into_iter()
+}
+
+pub fn parse_reader<F: FromStr>(reader: impl Read) -> F
+where
+    F::Err: Into<Error>,
+{
+    try_parse_reader(reader).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn parse_reader_lines<F: FromStr>(reader: impl BufRead) -> impl Iterator<Item = F>
+where
+    F::Err: Into<Error>,
+{
+    try_parse_reader_lines(reader)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .into_iter()
+}
+
+/// Accepts any string: `&'static str` constants, borrowed slices or owned `String`s
+pub fn parse_str_lines<F: FromStr>(input: impl AsRef<str>) -> impl Iterator<Item = F>
+where
+    F::Err: Into<Error>,
+{
+    try_parse_str_lines(input)
+        .unwrap_or_else(|err| panic!("{}", err))
+        .into_iter()
+}
+
+pub fn try_parse_file<F: FromStr>(filename: &str) -> Result<F, Error>
+where
+    F::Err: Into<Error>,
+{
+    try_parse_reader(File::open(filename)?)
+}
+
+pub fn try_parse_file_lines<F: FromStr>(filename: &str) -> Result<Vec<F>, Error>
+where
+    F::Err: Into<Error>,
+{
+    try_parse_reader_lines(BufReader::new(File::open(filename)?))
+}
+
+/// Reads everything from `reader` (a file, stdin, ...) and parses it as a whole
+pub fn try_parse_reader<F: FromStr>(mut reader: impl Read) -> Result<F, Error>
+where
+    F::Err: Into<Error>,
+{
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    try_parse_str(&input)
 }
 
-pub fn parse_file_lines<E: fmt::Debug, F: FromStr<Err = E>>(
-    filename: &str,
-) -> impl Iterator<Item = F> {
-    let file = File::open(filename).unwrap();
-    BufReader::new(file)
+pub fn try_parse_reader_lines<F: FromStr>(reader: impl BufRead) -> Result<Vec<F>, Error>
+where
+    F::Err: Into<Error>,
+{
+    reader
         .lines()
-        .map(|line| line.unwrap().parse().unwrap())
+        .enumerate()
+        .map(|(index, line)| {
+            line.map_err(Error::from)
+                .and_then(|line| parse_part(&line, &line))
+                .map_err(|err: Error| err.on_line(index + 1))
+        })
+        .collect()
+}
+
+/// Parses the whole of `input`, ignoring surrounding whitespace
+pub fn try_parse_str<F: FromStr>(input: &str) -> Result<F, Error>
+where
+    F::Err: Into<Error>,
+{
+    parse_part(input, input.trim())
+}
+
+pub fn try_parse_str_lines<F: FromStr>(input: impl AsRef<str>) -> Result<Vec<F>, Error>
+where
+    F::Err: Into<Error>,
+{
+    let input = input.as_ref();
+    input.lines().map(|line| parse_part(input, line)).collect()
+}
+
+/// Parses `part`, a slice of `input`, with errors positioned relative to `input`
+pub(crate) fn parse_part<F: FromStr>(input: &str, part: &str) -> Result<F, Error>
+where
+    F::Err: Into<Error>,
+{
+    part.parse()
+        .map_err(|err: F::Err| err.into().within(input, part))
+}
+
+/// Like [`str::split_once`] but fails with a positioned error when `delimiter` is missing
+pub(crate) fn split_once<'a>(input: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), Error> {
+    input
+        .split_once(delimiter)
+        .ok_or_else(|| Error::at(input, input, format!("expected '{}'", delimiter)))
+}
+
+/// Parses `part` of `input` formatted as `{prefix}{min}..{max}`
+pub(crate) fn parse_range<F: FromStr>(
+    input: &str,
+    part: &str,
+    prefix: &str,
+) -> Result<(F, F), Error>
+where
+    F::Err: Into<Error>,
+{
+    let range = part
+        .strip_prefix(prefix)
+        .ok_or_else(|| Error::at(input, part, format!("expected '{}'", prefix)))?;
+    let (min, max) = split_once(range, "..").map_err(|err| err.within(input, range))?;
+    Ok((parse_part(input, min)?, parse_part(input, max)?))
 }
 
-pub fn parse_str_lines<E: fmt::Debug, F: FromStr<Err = E>>(
-    input: &'static str,
-) -> impl Iterator<Item = F> {
-    input.lines().map(|line| line.parse().unwrap())
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn lines_from_reader() {
+        let numbers: Vec<u16> = parse_reader_lines(Cursor::new("199\n200\n208")).collect();
+        assert_eq!(vec![199, 200, 208], numbers);
+    }
+
+    #[test]
+    fn whole_input_from_reader_is_trimmed() {
+        let number: u64 = parse_reader("\n  1234\n\n".as_bytes());
+        assert_eq!(1234, number);
+    }
+
+    #[test]
+    fn lines_from_owned_string() {
+        let input: String = (1..=3).map(|n| format!("{}\n", n * 10)).collect();
+        let numbers: Vec<u8> = parse_str_lines(input).collect();
+        assert_eq!(vec![10, 20, 30], numbers);
+    }
+
+    #[test]
+    fn lines_from_borrowed_string() {
+        let input = String::from("1\n2");
+        let numbers: Vec<u8> = parse_str_lines(&input).collect();
+        assert_eq!(vec![1, 2], numbers);
+    }
+
+    #[test]
+    fn reader_errors_have_line_numbers() {
+        let error = try_parse_reader_lines::<u8>(Cursor::new("1\n2\nthree")).unwrap_err();
+        assert_eq!(Some(3), error.line);
+    }
+
+    #[test]
+    fn whole_input_errors_are_positioned_in_untrimmed_input() {
+        let error = try_parse_str::<u8>("\n\n x").unwrap_err();
+        assert_eq!((Some(3), Some(2)), (error.line, error.column));
+    }
 }
@@ -0,0 +1,267 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A cheapest path found by [`dijkstra`] or [`astar`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Found<N, C> {
+    pub cost: C,
+    /// Every node from the start up to and including the goal
+    pub path: Vec<N>,
+    pub stats: Stats,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Nodes taken from the queue and expanded, counting a node again when a cheaper path to it
+    /// was found after it had been expanded
+    pub visited: usize,
+    /// Distinct nodes reached
+    pub discovered: usize,
+}
+
+/// Where each reached node is in the list of reached nodes
+trait Indices<N> {
+    /// Index of `node`, or `None` after recording `index` for it
+    fn get_or_insert(&mut self, node: &N, index: usize) -> Option<usize>;
+}
+
+impl<N: Clone + Eq + Hash> Indices<N> for HashMap<N, usize> {
+    fn get_or_insert(&mut self, node: &N, index: usize) -> Option<usize> {
+        match self.get(node) {
+            Some(&found) => Some(found),
+            None => {
+                self.insert(node.clone(), index);
+                None
+            }
+        }
+    }
+}
+
+/// Indices in a vector at the position `F` gives each node, growing as needed
+struct Dense<F> {
+    position: F,
+    indices: Vec<usize>,
+}
+
+impl<N, F: FnMut(&N) -> usize> Indices<N> for Dense<F> {
+    fn get_or_insert(&mut self, node: &N, index: usize) -> Option<usize> {
+        let position = (self.position)(node);
+        if position >= self.indices.len() {
+            self.indices.resize(position + 1, usize::MAX);
+        }
+        match self.indices[position] {
+            usize::MAX => {
+                self.indices[position] = index;
+                None
+            }
+            found => Some(found),
+        }
+    }
+}
+
+/// Nodes reached so far, with the cheapest cost found and the node it came from, kept in
+/// separate vectors so the hot loop only touches the costs
+struct Reached<N, C> {
+    nodes: Vec<N>,
+    costs: Vec<C>,
+    /// `usize::MAX` for the start
+    parents: Vec<usize>,
+}
+
+impl<N, C> Reached<N, C> {
+    fn push(&mut self, node: N, cost: C, parent: usize) -> usize {
+        self.nodes.push(node);
+        self.costs.push(cost);
+        self.parents.push(parent);
+        self.nodes.len() - 1
+    }
+
+    /// Nodes from the start to `goal`
+    fn path(self, goal: usize) -> Vec<N> {
+        let mut indices = vec![goal];
+        while let Some(&parent) = self.parents.get(*indices.last().unwrap()) {
+            if parent == usize::MAX {
+                break;
+            }
+            indices.push(parent);
+        }
+        let mut nodes: Vec<Option<N>> = self.nodes.into_iter().map(Some).collect();
+        indices
+            .into_iter()
+            .rev()
+            .map(|index| nodes[index].take().unwrap())
+            .collect()
+    }
+}
+
+/// Cheapest path from `start` to a node for which `is_goal` holds.
+///
+/// `successors` yields the neighbours of a node with the cost of the step to them. The zero cost is
+/// `C::default()`.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`] for nodes that `position` maps to distinct small numbers, like grid cells.
+///
+/// Reached nodes are looked up in a vector indexed by position instead of a hash map, which is
+/// much faster when most positions get reached.
+pub fn dijkstra_dense<N, C, I>(
+    start: N,
+    position: impl FnMut(&N) -> usize,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let indices = Dense {
+        position,
+        indices: Vec::new(),
+    };
+    search(indices, start, successors, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], but explores nodes in order of cost so far plus `heuristic`.
+///
+/// The heuristic must never overestimate the remaining cost to a goal, otherwise the path found
+/// might not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    search(HashMap::new(), start, successors, heuristic, is_goal)
+}
+
+fn search<N, C, I>(
+    mut indices: impl Indices<N>,
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Found<N, C>>
+where
+    N: Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let zero = C::default();
+    let mut reached = Reached {
+        nodes: Vec::new(),
+        costs: Vec::new(),
+        parents: Vec::new(),
+    };
+    let mut queue = BinaryHeap::new();
+    let mut stats = Stats::default();
+
+    queue.push(Reverse((heuristic(&start), zero, 0)));
+    indices.get_or_insert(&start, 0);
+    reached.push(start, zero, usize::MAX);
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if cost > reached.costs[index] {
+            // a cheaper path to this node was queued after this one
+            continue;
+        }
+        stats.visited += 1;
+        let node = reached.nodes[index].clone();
+        if is_goal(&node) {
+            stats.discovered = reached.nodes.len();
+            return Some(Found {
+                cost,
+                path: reached.path(index),
+                stats,
+            });
+        }
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            let next_index = match indices.get_or_insert(&next, reached.nodes.len()) {
+                Some(next_index) => {
+                    if next_cost >= reached.costs[next_index] {
+                        continue;
+                    }
+                    reached.costs[next_index] = next_cost;
+                    reached.parents[next_index] = index;
+                    next_index
+                }
+                None => reached.push(next, next_cost, index),
+            };
+            let estimate = next_cost + heuristic(&reached.nodes[next_index]);
+            queue.push(Reverse((estimate, next_cost, next_index)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps of +1 cost 1, doubling costs 3
+    fn successors(&n: &u32) -> [(u32, u32); 2] {
+        [(n + 1, 1), (n * 2, 3)]
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_path() {
+        let found = dijkstra(1, successors, |&n| n == 20).unwrap();
+        assert_eq!(vec![1, 2, 3, 4, 5, 10, 20], found.path);
+        assert_eq!(10, found.cost);
+    }
+
+    #[test]
+    fn astar_visits_fewer_nodes_than_dijkstra() {
+        let target = 200;
+        let by_dijkstra = dijkstra(1, successors, |&n| n == target).unwrap();
+        // below the target at least one more step is needed
+        let heuristic = |&n: &u32| if n < target { 1 } else { 0 };
+        let by_astar = astar(1, successors, heuristic, |&n| n == target).unwrap();
+        assert_eq!(by_dijkstra.cost, by_astar.cost);
+        assert!(by_astar.stats.visited <= by_dijkstra.stats.visited);
+    }
+
+    #[test]
+    fn dense_indices_find_the_same_path() {
+        let found = dijkstra_dense(1, |&n| n as usize, successors, |&n| n == 20).unwrap();
+        assert_eq!(dijkstra(1, successors, |&n| n == 20), Some(found));
+    }
+
+    #[test]
+    fn unreachable_goal_is_none() {
+        let found = dijkstra(0, |&n: &u32| (n < 10).then(|| (n + 1, 1)), |&n| n == 20);
+        assert_eq!(None, found);
+    }
+
+    #[test]
+    fn start_can_be_the_goal() {
+        let found = dijkstra('a', |_| None, |&c| c == 'a').unwrap();
+        assert_eq!((0, vec!['a']), (found.cost, found.path));
+        assert_eq!(
+            Stats {
+                visited: 1,
+                discovered: 1
+            },
+            found.stats
+        );
+    }
+}
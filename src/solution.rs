@@ -0,0 +1,166 @@
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, Error,
+};
+
+/// A single day of the advent: how to parse the puzzle input and how to solve both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input: Clone + 'static;
+    type Part1: fmt::Display;
+    type Part2: fmt::Display;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    fn part_1(input: Self::Input) -> Self::Part1;
+
+    fn part_2(input: Self::Input) -> Self::Part2;
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            _ => Err(Error::new(format!("invalid part '{}'", input))),
+        }
+    }
+}
+
+/// Type erased [`Solution`] so days with different input and answer types can live in one registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, Error>;
+
+    /// `input` must be the result of [`Puzzle::parse`] on the same day
+    fn solve(&self, input: &dyn Any, part: Part) -> String;
+
+    fn run(&self, input: &str, part: Part) -> Result<String, Error> {
+        Ok(self.solve(self.parse(input)?.as_ref(), part))
+    }
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, Error> {
+        match S::parse(input) {
+            Ok(input) => Ok(Box::new(input)),
+            Err(err) => Err(err.for_day(S::DAY)),
+        }
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> String {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .unwrap_or_else(|| panic!("input was not parsed by day {}", S::DAY))
+            .clone();
+        match part {
+            Part::One => S::part_1(input).to_string(),
+            Part::Two => S::part_2(input).to_string(),
+        }
+    }
+}
+
+static DAYS: [&dyn Puzzle; 24] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+];
+
+/// All registered days, ordered by day number
+pub fn days() -> &'static [&'static dyn Puzzle] {
+    &DAYS
+}
+
+pub fn day(number: u8) -> Option<&'static dyn Puzzle> {
+    DAYS.iter().copied().find(|puzzle| puzzle.day() == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_days_are_registered_in_order() {
+        let numbers: Vec<u8> = days().iter().map(|puzzle| puzzle.day()).collect();
+        assert_eq!((1..=24).collect::<Vec<u8>>(), numbers);
+    }
+
+    #[test]
+    fn unknown_day_is_none() {
+        assert!(day(0).is_none());
+        assert!(day(25).is_none());
+    }
+
+    #[test]
+    fn run_day_6_example() {
+        let puzzle = day(6).unwrap();
+        assert_eq!(Ok("5934".to_owned()), puzzle.run("3,4,3,1,2", Part::One));
+        assert_eq!(
+            Ok("26984457539".to_owned()),
+            puzzle.run("3,4,3,1,2", Part::Two)
+        );
+    }
+
+    #[test]
+    fn parse_errors_carry_the_day() {
+        let err = day(6).unwrap().parse("3,4,x").unwrap_err();
+        assert_eq!(Some(6), err.day);
+        assert_eq!((Some(1), Some(5)), (err.line, err.column));
+    }
+
+    #[test]
+    fn parsed_input_can_be_reused() {
+        let puzzle = day(17).unwrap();
+        let input = puzzle.parse("target area: x=20..30, y=-10..-5").unwrap();
+        assert_eq!("45", puzzle.solve(input.as_ref(), Part::One));
+        assert_eq!("112", puzzle.solve(input.as_ref(), Part::Two));
+    }
+}
@@ -0,0 +1,228 @@
+use std::any::Any;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::{day, parse_part, Error, Part, Puzzle};
+
+/// One line of an answers manifest: `<day> <part> <input path> <answer>`.
+///
+/// Newlines in the answer are written as `\n` so multi-line answers fit on one line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expectation {
+    pub day: u8,
+    pub part: Part,
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub expectations: Vec<Expectation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Mismatch {
+        actual: String,
+    },
+    /// The day is not registered, or its input could not be read or parsed
+    Fail(Error),
+}
+
+#[derive(Clone, Debug)]
+pub struct Report<'a> {
+    pub expectation: &'a Expectation,
+    pub outcome: Outcome,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl Manifest {
+    /// Reads a manifest file, input paths are resolved relative to the manifest itself
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let input = fs::read_to_string(path)
+            .map_err(|err| Error::new(format!("cannot read '{}': {}", path.display(), err)))?;
+        let mut manifest: Self = input.parse()?;
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for expectation in &mut manifest.expectations {
+            expectation.input = base.join(&expectation.input);
+        }
+        Ok(manifest)
+    }
+
+    pub fn verify(&self) -> impl Iterator<Item = Report<'_>> + '_ {
+        self.expectations.iter().map(Expectation::verify)
+    }
+}
+
+impl Expectation {
+    pub fn verify(&self) -> Report<'_> {
+        let start = Instant::now();
+        let parsed = self.parse_input();
+        let parse_time = start.elapsed();
+        let (outcome, solve_time) = match parsed {
+            Ok((puzzle, input)) => {
+                let start = Instant::now();
+                let actual = puzzle.solve(input.as_ref(), self.part);
+                let solve_time = start.elapsed();
+                if actual == self.answer {
+                    (Outcome::Pass, solve_time)
+                } else {
+                    (Outcome::Mismatch { actual }, solve_time)
+                }
+            }
+            Err(err) => (Outcome::Fail(err), Duration::ZERO),
+        };
+        Report {
+            expectation: self,
+            outcome,
+            parse_time,
+            solve_time,
+        }
+    }
+
+    fn parse_input(&self) -> Result<(&'static dyn Puzzle, Box<dyn Any>), Error> {
+        let puzzle =
+            day(self.day).ok_or_else(|| Error::new(format!("day {} is not solved", self.day)))?;
+        let input = fs::read_to_string(&self.input).map_err(|err| {
+            Error::new(format!("cannot read '{}': {}", self.input.display(), err)).for_day(self.day)
+        })?;
+        Ok((puzzle, puzzle.parse(&input)?))
+    }
+}
+
+impl Report<'_> {
+    pub const fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Pass)
+    }
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Expectation { day, part, .. } = self.expectation;
+        let timings = format!("parse {:?}, solve {:?}", self.parse_time, self.solve_time);
+        match &self.outcome {
+            Outcome::Pass => write!(f, "PASS     day {:02} part {} ({})", day, part, timings),
+            Outcome::Mismatch { actual } => write!(
+                f,
+                "MISMATCH day {:02} part {}: expected {}, got {} ({})",
+                day,
+                part,
+                escape(&self.expectation.answer),
+                escape(actual),
+                timings
+            ),
+            Outcome::Fail(err) => write!(f, "FAIL     day {:02} part {}: {}", day, part, err),
+        }
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\n', "\\n")
+}
+
+/// Takes the next whitespace separated field from `rest`
+fn next_field<'a>(input: &str, rest: &mut &'a str, what: &str) -> Result<&'a str, Error> {
+    let trimmed = rest.trim_start();
+    if trimmed.is_empty() {
+        return Err(Error::at_end(input, format!("expected {}", what)));
+    }
+    let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let (field, remainder) = trimmed.split_at(end);
+    *rest = remainder;
+    Ok(field)
+}
+
+impl FromStr for Expectation {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut rest = input;
+        let day = parse_part(input, next_field(input, &mut rest, "a day")?)?;
+        let part = parse_part(input, next_field(input, &mut rest, "a part")?)?;
+        let path = next_field(input, &mut rest, "an input path")?;
+        let answer = rest.trim();
+        if answer.is_empty() {
+            return Err(Error::at_end(input, "expected an answer"));
+        }
+        Ok(Self {
+            day,
+            part,
+            input: PathBuf::from(path),
+            answer: answer.replace("\\n", "\n"),
+        })
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = Error;
+
+    /// Blank lines and lines starting with `#` are ignored
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let expectations = input
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| parse_part(input, line))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { expectations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "\
+        # day part input answer\n\
+        6 1 src/day06/input.txt 391888\n\
+        \n\
+        6 2 src/day06/input.txt 42\n\
+        13 2 src/day13/missing.txt #...\\n#...\\n\n\
+        25 1 src/day25/input.txt 0\
+    ";
+
+    #[test]
+    fn parse_manifest() {
+        let manifest: Manifest = MANIFEST.parse().unwrap();
+        assert_eq!(4, manifest.expectations.len());
+        assert_eq!(
+            Expectation {
+                day: 13,
+                part: Part::Two,
+                input: PathBuf::from("src/day13/missing.txt"),
+                answer: "#...\n#...\n".to_owned(),
+            },
+            manifest.expectations[2]
+        );
+    }
+
+    #[test]
+    fn missing_answer_is_an_error() {
+        let error = "# comment\n6 1 src/day06/input.txt"
+            .parse::<Manifest>()
+            .unwrap_err();
+        assert_eq!("line 2, column 24: expected an answer", error.to_string());
+    }
+
+    #[test]
+    fn verify_reports_pass_mismatch_and_fail() {
+        let manifest: Manifest = MANIFEST.parse().unwrap();
+        let outcomes: Vec<Outcome> = manifest.verify().map(|report| report.outcome).collect();
+        assert_eq!(Outcome::Pass, outcomes[0]);
+        assert_eq!(
+            Outcome::Mismatch {
+                actual: "1754597645339".to_owned()
+            },
+            outcomes[1]
+        );
+        assert!(matches!(&outcomes[2], Outcome::Fail(err) if err.day == Some(13)));
+        assert!(
+            matches!(&outcomes[3], Outcome::Fail(err) if err.message == "day 25 is not solved")
+        );
+    }
+}